@@ -1,12 +1,67 @@
-use fxhash::FxHashMap;
-use std::fmt;
-use std::fmt::Debug;
+use alloc::boxed::Box;
+use core::fmt;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+#[cfg(feature = "serde")]
+use serde_crate::de::{MapAccess, Visitor};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
 use thin_vec::ThinVec;
 
+/// Hash map of the children of a node with many of them. With the standard
+/// library, it is the fast hash map (FxHashMap); without it, the `hashbrown`
+/// map with its default hasher, which only needs `alloc`.
+#[cfg(feature = "std")]
+type ChildMap<NodeType> = fxhash::FxHashMap<char, NodeType>;
+#[cfg(not(feature = "std"))]
+type ChildMap<NodeType> = hashbrown::HashMap<char, NodeType>;
+
+#[cfg(feature = "std")]
+type ChildMapIntoIter<NodeType> = std::collections::hash_map::IntoIter<char, NodeType>;
+#[cfg(not(feature = "std"))]
+type ChildMapIntoIter<NodeType> = hashbrown::hash_map::IntoIter<char, NodeType>;
+
+/// Settings for storing the children of every node in a trie.
+/// Tries with few children per node, like ASCII dictionaries, are served
+/// well by vectors, while tries with a large alphabet, like CJK text,
+/// need hash maps much sooner.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageLayout {
+    /// Maximum number of children kept in a vector. A node with more children
+    /// keeps them in a hash map, until it is left with half of this number
+    /// of children or fewer.
+    pub promotion_threshold: usize,
+    /// Whether vectors are kept sorted by their characters,
+    /// so that children are found by binary search.
+    pub sorted_small: bool,
+    /// Whether a node with more than four children, all of them ASCII characters,
+    /// keeps them in an array indexed by a bitmap of their characters.
+    pub dense_ascii: bool,
+}
+
+impl Default for StorageLayout {
+    fn default() -> Self {
+        StorageLayout {
+            promotion_threshold: 32,
+            sorted_small: false,
+            dense_ascii: true,
+        }
+    }
+}
+
 /// A multi-typed container for storing child nodes.
 /// - An empty discriminant (no allocations) is used when there are no children
-/// - A vector (ThinVec) variant is used when there are less than 32 children
-/// - A fast hash map (FxHashMap) is used when there are more than 32 children
+/// - A vector (ThinVec) variant is used when there are up to 'promotion_threshold'
+///   children, searched linearly or kept sorted and searched by binary search
+/// - A fast hash map (FxHashMap, or `hashbrown`'s map without the standard library)
+///   is used when there are more children
+/// - A bitmap indexed array is used instead of both when there are more than
+///   four children and all of them are ASCII characters
 ///
 /// This preserves much more space than always keeping the allocated hash map.
 #[derive(Default)]
@@ -14,24 +69,130 @@ pub enum ChildStorage<NodeType> {
     #[default]
     Empty,
     Small(ThinVec<(char, NodeType)>),
-    Large(Box<FxHashMap<char, NodeType>>),
+    SortedSmall(ThinVec<(char, NodeType)>),
+    Large(Box<ChildMap<NodeType>>),
+    DenseAscii(Box<AsciiChildren<NodeType>>),
+}
+
+/// Number of ASCII children a node must exceed to store them densely.
+/// Below it, the separate allocation of the bitmap costs more than
+/// the characters it saves.
+const DENSE_ASCII_MIN_CHILDREN: usize = 4;
+
+/// Every ASCII character, for lending characters that aren't stored.
+static ASCII_CHARACTERS: [char; 128] = {
+    let mut characters = ['\0'; 128];
+    let mut i = 0;
+    while i < 128 {
+        characters[i] = i as u8 as char;
+        i += 1;
+    }
+    characters
+};
+
+/// Children whose characters are all ASCII, packed in the order of their characters.
+/// Bit 'c' of the bitmap is set when character 'c' has a child, and the position
+/// of that child is the number of set bits below bit 'c', so no characters are stored.
+pub struct AsciiChildren<NodeType> {
+    bitmap: u128,
+    nodes: ThinVec<NodeType>,
+}
+
+impl<NodeType> AsciiChildren<NodeType> {
+    /// Builds the children from entries with ASCII characters only.
+    fn from_entries(mut entries: ThinVec<(char, NodeType)>) -> Self {
+        entries.sort_unstable_by_key(|(k, _)| *k);
+
+        AsciiChildren {
+            bitmap: entries
+                .iter()
+                .fold(0, |bitmap, (k, _)| bitmap | 1 << *k as u32),
+            nodes: entries.into_iter().map(|(_, node)| node).collect(),
+        }
+    }
+
+    /// Returns the position of the child of a character, if there is one.
+    fn position(&self, key: char) -> Option<usize> {
+        if !key.is_ascii() {
+            return None;
+        }
+
+        let bit = 1 << key as u32;
+        (self.bitmap & bit != 0).then(|| (self.bitmap & (bit - 1)).count_ones() as usize)
+    }
+
+    /// Inserts the child of an ASCII character, replacing the previous one.
+    fn insert(&mut self, key: char, node: NodeType) {
+        let bit = 1 << key as u32;
+        let position = (self.bitmap & (bit - 1)).count_ones() as usize;
+
+        if self.bitmap & bit != 0 {
+            self.nodes[position] = node;
+        } else {
+            self.nodes.insert(position, node);
+            self.bitmap |= bit;
+        }
+    }
+
+    /// Removes the child of a character.
+    fn remove(&mut self, key: char) -> Option<NodeType> {
+        let position = self.position(key)?;
+        self.bitmap &= !(1 << key as u32);
+
+        Some(self.nodes.remove(position))
+    }
+
+    /// Returns the characters of the children in order.
+    fn keys(&self) -> AsciiKeys<'_> {
+        AsciiKeys::new(self.bitmap)
+    }
+
+    /// Consumes the children and returns them with their characters.
+    fn into_entries(self) -> impl Iterator<Item = (char, NodeType)> {
+        AsciiKeys::new(self.bitmap).copied().zip(self.nodes)
+    }
+}
+
+/// Iterator over the characters of a bitmap, from the lowest.
+pub struct AsciiKeys<'a>(u128, PhantomData<&'a char>);
+
+impl AsciiKeys<'_> {
+    fn new(bitmap: u128) -> Self {
+        AsciiKeys(bitmap, PhantomData)
+    }
+}
+
+impl<'a> Iterator for AsciiKeys<'a> {
+    type Item = &'a char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let character = &ASCII_CHARACTERS[self.0.trailing_zeros() as usize];
+        self.0 &= self.0 - 1;
+
+        Some(character)
+    }
 }
 
 impl<NodeType> ChildStorage<NodeType> {
     /// Inserts a new defaulted node into the children container.
-    pub fn insert_new(&mut self, key: char)
+    pub fn insert_new(&mut self, key: char, layout: StorageLayout)
     where
         NodeType: Default,
     {
-        self.insert_direct(key, NodeType::default());
+        self.insert_direct(key, NodeType::default(), layout);
     }
 
     /// Returns the number of children
     pub fn len(&self) -> usize {
         match self {
             ChildStorage::Empty => 0,
-            ChildStorage::Small(v) => v.len(),
+            ChildStorage::Small(v) | ChildStorage::SortedSmall(v) => v.len(),
             ChildStorage::Large(m) => m.len(),
+            ChildStorage::DenseAscii(a) => a.nodes.len(),
         }
     }
 
@@ -40,7 +201,12 @@ impl<NodeType> ChildStorage<NodeType> {
         match self {
             ChildStorage::Empty => None,
             ChildStorage::Small(v) => v.iter().find(|(c, _)| *c == key).map(|(_, node)| node),
+            ChildStorage::SortedSmall(v) => v
+                .binary_search_by_key(&key, |(c, _)| *c)
+                .ok()
+                .map(|pos| &v[pos].1),
             ChildStorage::Large(m) => m.get(&key),
+            ChildStorage::DenseAscii(a) => a.position(key).map(|pos| &a.nodes[pos]),
         }
     }
 
@@ -49,34 +215,63 @@ impl<NodeType> ChildStorage<NodeType> {
         match self {
             ChildStorage::Empty => None,
             ChildStorage::Small(v) => v.iter_mut().find(|(c, _)| *c == key).map(|(_, node)| node),
+            ChildStorage::SortedSmall(v) => v
+                .binary_search_by_key(&key, |(c, _)| *c)
+                .ok()
+                .map(|pos| &mut v[pos].1),
             ChildStorage::Large(m) => m.get_mut(&key),
+            ChildStorage::DenseAscii(a) => a.position(key).map(|pos| &mut a.nodes[pos]),
         }
     }
 
     /// Unified iterator over (&char, &TrieDatalessNode)
     pub fn iter(&self) -> Box<dyn Iterator<Item = (&char, &NodeType)> + '_> {
         match self {
-            ChildStorage::Empty => Box::new(std::iter::empty()),
-            ChildStorage::Small(v) => Box::new(v.iter().map(|(c, n)| (c, n))),
+            ChildStorage::Empty => Box::new(core::iter::empty()),
+            ChildStorage::Small(v) | ChildStorage::SortedSmall(v) => {
+                Box::new(v.iter().map(|(c, n)| (c, n)))
+            }
             ChildStorage::Large(m) => Box::new(m.iter()),
+            ChildStorage::DenseAscii(a) => Box::new(a.keys().zip(a.nodes.iter())),
+        }
+    }
+
+    /// Unified iterator over (&char, &mut TrieDatalessNode)
+    pub fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (&char, &mut NodeType)> + '_> {
+        match self {
+            ChildStorage::Empty => Box::new(core::iter::empty()),
+            ChildStorage::Small(v) | ChildStorage::SortedSmall(v) => {
+                Box::new(v.iter_mut().map(|(c, n)| (&*c, n)))
+            }
+            ChildStorage::Large(m) => Box::new(m.iter_mut()),
+            ChildStorage::DenseAscii(a) => {
+                Box::new(AsciiKeys::new(a.bitmap).zip(a.nodes.iter_mut()))
+            }
         }
     }
 
     /// Unified iterator over &TrieDatalessNode
     pub fn values(&self) -> Box<dyn Iterator<Item = &NodeType> + '_> {
         match self {
-            ChildStorage::Empty => Box::new(std::iter::empty()),
-            ChildStorage::Small(v) => Box::new(v.iter().map(|(_, n)| n)),
+            ChildStorage::Empty => Box::new(core::iter::empty()),
+            ChildStorage::Small(v) | ChildStorage::SortedSmall(v) => {
+                Box::new(v.iter().map(|(_, n)| n))
+            }
             ChildStorage::Large(m) => Box::new(m.values()),
+            ChildStorage::DenseAscii(a) => Box::new(a.nodes.iter()),
         }
     }
 
+    #[cfg(feature = "data")]
     /// Unified iterator over &mut TrieDatalessNode
     pub fn values_mut(&mut self) -> Box<dyn Iterator<Item = &mut NodeType> + '_> {
         match self {
-            ChildStorage::Empty => Box::new(std::iter::empty()),
-            ChildStorage::Small(v) => Box::new(v.iter_mut().map(|(_, n)| n)),
+            ChildStorage::Empty => Box::new(core::iter::empty()),
+            ChildStorage::Small(v) | ChildStorage::SortedSmall(v) => {
+                Box::new(v.iter_mut().map(|(_, n)| n))
+            }
             ChildStorage::Large(m) => Box::new(m.values_mut()),
+            ChildStorage::DenseAscii(a) => Box::new(a.nodes.iter_mut()),
         }
     }
 
@@ -84,12 +279,18 @@ impl<NodeType> ChildStorage<NodeType> {
     pub fn is_empty(&self) -> bool {
         match self {
             ChildStorage::Empty => true,
-            ChildStorage::Small(_) | ChildStorage::Large(_) => false,
+            ChildStorage::Small(_)
+            | ChildStorage::SortedSmall(_)
+            | ChildStorage::Large(_)
+            | ChildStorage::DenseAscii(_) => false,
         }
     }
 
-    /// Removes a child based on the passed character.
-    pub fn remove(&mut self, key: char) -> Option<NodeType> {
+    /// Removes a child based on the passed character. A hash map that is left
+    /// with half of the promotion threshold or fewer children is switched back
+    /// to a vector, so that nodes that lost their children also lose the map.
+    /// The same goes for dense children that are left with two children or fewer.
+    pub fn remove(&mut self, key: char, layout: StorageLayout) -> Option<NodeType> {
         match self {
             ChildStorage::Empty => None,
             ChildStorage::Small(vec) => {
@@ -101,7 +302,40 @@ impl<NodeType> ChildStorage<NodeType> {
                 }
                 Some(node)
             }
-            ChildStorage::Large(map) => map.remove(&key),
+            ChildStorage::SortedSmall(vec) => {
+                let pos = vec.binary_search_by_key(&key, |(k, _)| *k).ok()?;
+                let (_, node) = vec.remove(pos);
+
+                if vec.is_empty() {
+                    *self = ChildStorage::Empty;
+                }
+                Some(node)
+            }
+            ChildStorage::Large(map) => {
+                let node = map.remove(&key)?;
+
+                if map.len() <= layout.promotion_threshold / 2 {
+                    *self = Self::from_entries(map.drain().collect(), layout);
+                }
+
+                Some(node)
+            }
+            ChildStorage::DenseAscii(children) => {
+                let node = children.remove(key)?;
+
+                if children.nodes.len() <= DENSE_ASCII_MIN_CHILDREN / 2 {
+                    let ChildStorage::DenseAscii(children) = core::mem::take(self) else {
+                        unreachable!()
+                    };
+                    let layout = StorageLayout {
+                        dense_ascii: false,
+                        ..layout
+                    };
+                    *self = Self::from_entries(children.into_entries().collect(), layout);
+                }
+
+                Some(node)
+            }
         }
     }
 
@@ -113,44 +347,156 @@ impl<NodeType> ChildStorage<NodeType> {
 
         match (self, other) {
             (ChildStorage::Empty, ChildStorage::Empty) => true,
-            (ChildStorage::Small(v1), ChildStorage::Small(v2)) => {
-                v1.iter().all(|(k1, _)| v2.iter().any(|(k2, _)| k1 == k2))
-            }
             (ChildStorage::Large(m1), ChildStorage::Large(m2)) => {
                 m1.keys().all(|k| m2.contains_key(k))
             }
-            (ChildStorage::Small(v), ChildStorage::Large(m))
-            | (ChildStorage::Large(m), ChildStorage::Small(v)) => {
-                v.iter().all(|(k, _)| m.contains_key(k))
-            }
-            _ => false,
+            _ => self.iter().all(|(k, _)| other.get(*k).is_some()),
         }
     }
 
     /// Inserts the passed node into the collection. Switches up the type of
-    /// collection in case the limit gets passed.
-    pub fn insert_direct(&mut self, key: char, node: NodeType) {
+    /// collection in case the limit of the layout gets passed, or in case
+    /// the children can be stored densely.
+    pub fn insert_direct(&mut self, key: char, node: NodeType, layout: StorageLayout) {
         match self {
             ChildStorage::Empty => {
                 let mut v = ThinVec::with_capacity(1);
                 v.push((key, node));
-                *self = ChildStorage::Small(v);
+                *self = if layout.sorted_small {
+                    ChildStorage::SortedSmall(v)
+                } else {
+                    ChildStorage::Small(v)
+                };
+            }
+            ChildStorage::Small(vec) | ChildStorage::SortedSmall(vec)
+                if vec.len() >= layout.promotion_threshold
+                    || Self::fits_dense_ascii(vec, key, layout) =>
+            {
+                let mut entries = core::mem::take(vec);
+                entries.push((key, node));
+                *self = Self::from_entries(entries, layout);
             }
             ChildStorage::Small(vec) => {
-                if vec.len() < 32 {
-                    vec.push((key, node));
-                } else {
-                    let mut map = FxHashMap::default();
-                    for (k, v) in vec.drain(..) {
-                        map.insert(k, v);
-                    }
-                    map.insert(key, node);
-                    *self = ChildStorage::Large(Box::new(map));
-                }
+                vec.push((key, node));
+            }
+            ChildStorage::SortedSmall(vec) => {
+                let pos = vec.partition_point(|(k, _)| *k < key);
+                vec.insert(pos, (key, node));
             }
             ChildStorage::Large(map) => {
                 map.insert(key, node);
             }
+            ChildStorage::DenseAscii(children) if key.is_ascii() => {
+                children.insert(key, node);
+            }
+            // A character outside of ASCII can't be stored densely.
+            ChildStorage::DenseAscii(_) => {
+                let ChildStorage::DenseAscii(children) = core::mem::take(self) else {
+                    unreachable!()
+                };
+                let mut entries = children.into_entries().collect::<ThinVec<_>>();
+                entries.push((key, node));
+                *self = Self::from_entries(entries, layout);
+            }
+        }
+    }
+
+    /// Whether a vector of children, after adding the child of 'key',
+    /// should be stored densely.
+    fn fits_dense_ascii(vec: &ThinVec<(char, NodeType)>, key: char, layout: StorageLayout) -> bool {
+        layout.dense_ascii
+            && key.is_ascii()
+            && vec.len() >= DENSE_ASCII_MIN_CHILDREN
+            && vec.iter().all(|(k, _)| k.is_ascii())
+    }
+
+    /// Builds the collection that the layout chooses for the given children.
+    fn from_entries(mut entries: ThinVec<(char, NodeType)>, layout: StorageLayout) -> Self {
+        if entries.is_empty() {
+            ChildStorage::Empty
+        } else if layout.dense_ascii
+            && entries.len() > DENSE_ASCII_MIN_CHILDREN
+            && entries.iter().all(|(k, _)| k.is_ascii())
+        {
+            ChildStorage::DenseAscii(Box::new(AsciiChildren::from_entries(entries)))
+        } else if entries.len() > layout.promotion_threshold {
+            ChildStorage::Large(Box::new(entries.into_iter().collect()))
+        } else if layout.sorted_small {
+            entries.sort_unstable_by_key(|(k, _)| *k);
+            ChildStorage::SortedSmall(entries)
+        } else {
+            ChildStorage::Small(entries)
+        }
+    }
+
+    /// Rebuilds the collection that the layout chooses for the current children.
+    #[cfg(feature = "serde")]
+    pub fn relayout(&mut self, layout: StorageLayout) {
+        let entries = core::mem::take(self).into_iter().collect::<ThinVec<_>>();
+        *self = Self::from_entries(entries, layout);
+    }
+
+    /// Returns an estimate of the heap memory held by the container itself,
+    /// not counting the memory the children hold. Vectors are counted by their
+    /// capacity and header, maps by their buckets with one control byte each.
+    pub fn heap_bytes(&self) -> usize {
+        match self {
+            ChildStorage::Empty => 0,
+            ChildStorage::Small(vec) | ChildStorage::SortedSmall(vec) if vec.capacity() == 0 => 0,
+            ChildStorage::Small(vec) | ChildStorage::SortedSmall(vec) => {
+                2 * size_of::<usize>() + vec.capacity() * size_of::<(char, NodeType)>()
+            }
+            ChildStorage::Large(map) => {
+                // Maps keep at most 7/8 of their buckets full.
+                let buckets = (map.capacity() * 8 / 7).next_power_of_two();
+                size_of::<ChildMap<NodeType>>() + buckets * (size_of::<(char, NodeType)>() + 1)
+            }
+            ChildStorage::DenseAscii(children) => {
+                let nodes_bytes = match children.nodes.capacity() {
+                    0 => 0,
+                    capacity => 2 * size_of::<usize>() + capacity * size_of::<NodeType>(),
+                };
+                size_of::<AsciiChildren<NodeType>>() + nodes_bytes
+            }
+        }
+    }
+
+    /// Consumes the container and maps every child node to a new node,
+    /// keeping the same type of collection.
+    #[cfg(feature = "data")]
+    pub fn map<NewNodeType>(
+        self,
+        mut f: impl FnMut(char, NodeType) -> NewNodeType,
+    ) -> ChildStorage<NewNodeType> {
+        match self {
+            ChildStorage::Empty => ChildStorage::Empty,
+            ChildStorage::Small(vec) => {
+                let mut new_vec = ThinVec::with_capacity(vec.len());
+                for (key, node) in vec {
+                    new_vec.push((key, f(key, node)));
+                }
+                ChildStorage::Small(new_vec)
+            }
+            ChildStorage::SortedSmall(vec) => {
+                let mut new_vec = ThinVec::with_capacity(vec.len());
+                for (key, node) in vec {
+                    new_vec.push((key, f(key, node)));
+                }
+                ChildStorage::SortedSmall(new_vec)
+            }
+            ChildStorage::Large(map) => ChildStorage::Large(Box::new(
+                map.into_iter()
+                    .map(|(key, node)| (key, f(key, node)))
+                    .collect(),
+            )),
+            ChildStorage::DenseAscii(children) => {
+                let bitmap = children.bitmap;
+                let nodes = children
+                    .into_entries()
+                    .map(|(key, node)| f(key, node))
+                    .collect();
+                ChildStorage::DenseAscii(Box::new(AsciiChildren { bitmap, nodes }))
+            }
         }
     }
 }
@@ -163,12 +509,14 @@ impl<NodeType: PartialEq> PartialEq for ChildStorage<NodeType> {
 
         match (self, other) {
             (Self::Empty, Self::Empty) => true,
-            (Self::Small(v1), Self::Small(v2)) => v1.iter().all(|(k1, n1)| {
-                v2.iter()
-                    .find(|(k2, _)| k1 == k2)
-                    .map(|(_, n2)| n1 == n2)
-                    .unwrap_or(false)
-            }),
+            (Self::Small(v1) | Self::SortedSmall(v1), Self::Small(v2) | Self::SortedSmall(v2)) => {
+                v1.iter().all(|(k1, n1)| {
+                    v2.iter()
+                        .find(|(k2, _)| k1 == k2)
+                        .map(|(_, n2)| n1 == n2)
+                        .unwrap_or(false)
+                })
+            }
             (Self::Large(m1), Self::Large(m2)) => m1 == m2,
             _ => self
                 .iter()
@@ -181,7 +529,7 @@ impl<NodeType: Debug> Debug for ChildStorage<NodeType> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Empty => write!(f, "{{}}"),
-            Self::Small(vec) => {
+            Self::Small(vec) | Self::SortedSmall(vec) => {
                 let mut map = f.debug_map();
                 for (c, node) in vec {
                     map.entry(c, node);
@@ -189,7 +537,66 @@ impl<NodeType: Debug> Debug for ChildStorage<NodeType> {
                 map.finish()
             }
             Self::Large(map) => f.debug_map().entries(map.iter()).finish(),
+            Self::DenseAscii(_) => f.debug_map().entries(self.iter()).finish(),
+        }
+    }
+}
+
+/// Function rebuilds the children of every node of the tree with the given root
+/// according to 'layout', for a trie that was just deserialized.
+#[cfg(feature = "serde")]
+pub(crate) fn relayout_tree<NodeType>(
+    root: &mut NodeType,
+    layout: StorageLayout,
+    children_mut: fn(&mut NodeType) -> &mut ChildStorage<NodeType>,
+) {
+    let mut stack = alloc::vec![root];
+
+    while let Some(node) = stack.pop() {
+        let children = children_mut(node);
+        children.relayout(layout);
+        stack.extend(children.values_mut());
+    }
+}
+
+/// Children are serialized as a map from their characters to their nodes,
+/// whichever variant holds them.
+#[cfg(feature = "serde")]
+impl<NodeType: Serialize> Serialize for ChildStorage<NodeType> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// Children are deserialized from a map into the collection that the default
+/// layout chooses for them. Tries lay them out again with their own layout.
+#[cfg(feature = "serde")]
+impl<'de, NodeType: Deserialize<'de>> Deserialize<'de> for ChildStorage<NodeType> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        struct ChildVisitor<NodeType>(PhantomData<NodeType>);
+
+        impl<'de, NodeType: Deserialize<'de>> Visitor<'de> for ChildVisitor<NodeType> {
+            type Value = ChildStorage<NodeType>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of characters to child nodes")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut entries = ThinVec::with_capacity(access.size_hint().unwrap_or(0));
+
+                while let Some(entry) = access.next_entry()? {
+                    entries.push(entry);
+                }
+
+                Ok(ChildStorage::from_entries(
+                    entries,
+                    StorageLayout::default(),
+                ))
+            }
         }
+
+        deserializer.deserialize_map(ChildVisitor(PhantomData))
     }
 }
 
@@ -197,7 +604,8 @@ impl<NodeType: Debug> Debug for ChildStorage<NodeType> {
 pub enum ChildIntoIter<NodeType> {
     Empty,
     Small(thin_vec::IntoIter<(char, NodeType)>),
-    Large(std::collections::hash_map::IntoIter<char, NodeType>),
+    Large(ChildMapIntoIter<NodeType>),
+    DenseAscii(core::iter::Zip<AsciiKeys<'static>, thin_vec::IntoIter<NodeType>>),
 }
 
 impl<NodeType> Iterator for ChildIntoIter<NodeType> {
@@ -208,6 +616,7 @@ impl<NodeType> Iterator for ChildIntoIter<NodeType> {
             ChildIntoIter::Empty => None,
             ChildIntoIter::Small(iter) => iter.next(),
             ChildIntoIter::Large(iter) => iter.next(),
+            ChildIntoIter::DenseAscii(iter) => iter.next().map(|(key, node)| (*key, node)),
         }
     }
 }
@@ -219,8 +628,13 @@ impl<NodeType> IntoIterator for ChildStorage<NodeType> {
     fn into_iter(self) -> Self::IntoIter {
         match self {
             ChildStorage::Empty => ChildIntoIter::Empty,
-            ChildStorage::Small(vec) => ChildIntoIter::Small(vec.into_iter()),
+            ChildStorage::Small(vec) | ChildStorage::SortedSmall(vec) => {
+                ChildIntoIter::Small(vec.into_iter())
+            }
             ChildStorage::Large(map) => ChildIntoIter::Large(map.into_iter()),
+            ChildStorage::DenseAscii(children) => {
+                ChildIntoIter::DenseAscii(AsciiKeys::new(children.bitmap).zip(children.nodes))
+            }
         }
     }
 }
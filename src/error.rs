@@ -0,0 +1,47 @@
+use core::fmt;
+
+/// Reasons for which the fallible methods of the tries fail,
+/// instead of silently doing nothing. More reasons may be added
+/// in later versions, so matches on it need a wildcard arm.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum TrieError {
+    /// No word in the trie begins with the given sequence of characters.
+    NotFound,
+    /// The given sequence of characters is only a prefix of other words,
+    /// and not a word itself.
+    NotAWord,
+    /// The trie can't hold the nodes needed for inserting the word.
+    CapacityExceeded,
+    /// The nodes needed for inserting the word would take more memory
+    /// than the limits of the trie allow.
+    MemoryExceeded,
+    /// The word has more characters than the limits of the trie allow.
+    WordTooLong,
+    /// The trie already has as many words as its limits allow.
+    TooManyWords,
+    /// The word already has as many data items as the limits of the trie allow.
+    TooManyData,
+    /// The word has no data or more than one data item,
+    /// where exactly one is needed.
+    DataCountMismatch,
+}
+
+impl fmt::Display for TrieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrieError::NotFound => f.write_str("no word begins with the given characters"),
+            TrieError::NotAWord => f.write_str("the given characters aren't a word in the trie"),
+            TrieError::CapacityExceeded => f.write_str("the trie can't hold any more nodes"),
+            TrieError::MemoryExceeded => f.write_str("the trie can't take any more memory"),
+            TrieError::WordTooLong => f.write_str("the word is longer than the trie allows"),
+            TrieError::TooManyWords => f.write_str("the trie can't hold any more words"),
+            TrieError::TooManyData => f.write_str("the word can't hold any more data"),
+            TrieError::DataCountMismatch => {
+                f.write_str("the word doesn't have exactly one data item")
+            }
+        }
+    }
+}
+
+impl core::error::Error for TrieError {}
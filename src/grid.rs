@@ -0,0 +1,112 @@
+use crate::trie_node::TrieNode;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Cells of a grid that follow each other in a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Adjacency {
+    /// Cells sharing a side: up, down, left and right.
+    Four,
+    /// Cells sharing a side or a corner, like in Boggle.
+    #[default]
+    Eight,
+}
+
+impl Adjacency {
+    /// Function returns the offsets of the neighbours of a cell, in rows and columns.
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Adjacency::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Adjacency::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// Function returns every word of the trie with the given root that is spelled by
+/// a path of adjacent cells of 'grid', where no cell is used twice in one path.
+/// Every word is returned once, in lexicographic order, with the (row, column) cells
+/// of the first path found for it. Paths are started from the cells in row-major order.
+///
+/// The trie is walked in lockstep with the paths, so a path is left as soon
+/// as the node of its word has no child for the character of the next cell.
+pub(crate) fn find_in_grid<N: TrieNode>(
+    root: &N,
+    grid: &[Vec<char>],
+    adjacency: Adjacency,
+) -> Vec<(String, Vec<(usize, usize)>)> {
+    let mut found_words = BTreeMap::new();
+    let mut is_used = grid
+        .iter()
+        .map(|row| vec![false; row.len()])
+        .collect::<Vec<_>>();
+
+    let mut word = String::new();
+    let mut path = Vec::<(usize, usize)>::new();
+    let mut stack = Vec::new();
+
+    for (start_row, cells) in grid.iter().enumerate() {
+        for (start_column, &character) in cells.iter().enumerate() {
+            if let Some(node) = root.children().get(character) {
+                stack.push(((start_row, start_column), node, 1));
+            }
+
+            while let Some(((row, column), node, depth)) = stack.pop() {
+                // Cells below the previous cell of the path are left.
+                while path.len() >= depth {
+                    let (row, column) = path.pop().unwrap();
+                    is_used[row][column] = false;
+                    word.pop();
+                }
+
+                is_used[row][column] = true;
+                path.push((row, column));
+                word.push(grid[row][column]);
+
+                if node.is_associated() && !found_words.contains_key(&word) {
+                    found_words.insert(word.clone(), path.clone());
+                }
+
+                for &(row_offset, column_offset) in adjacency.offsets().iter().rev() {
+                    let (Some(next_row), Some(next_column)) = (
+                        row.checked_add_signed(row_offset),
+                        column.checked_add_signed(column_offset),
+                    ) else {
+                        continue;
+                    };
+
+                    let Some(&next_character) =
+                        grid.get(next_row).and_then(|cells| cells.get(next_column))
+                    else {
+                        continue;
+                    };
+
+                    if is_used[next_row][next_column] {
+                        continue;
+                    }
+
+                    if let Some(child) = node.children().get(next_character) {
+                        stack.push(((next_row, next_column), child, depth + 1));
+                    }
+                }
+            }
+
+            for (row, column) in path.drain(..) {
+                is_used[row][column] = false;
+            }
+            word.clear();
+        }
+    }
+
+    found_words.into_iter().collect()
+}
@@ -0,0 +1,299 @@
+use crate::DataTrie;
+use crate::trie::{get_characters, word_tokens};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A single occurrence of a term in an indexed document.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Posting {
+    doc_id: usize,
+    position: usize,
+}
+
+impl Posting {
+    /// Returns the identifier of the document the term occurs in.
+    pub fn doc_id(&self) -> usize {
+        self.doc_id
+    }
+
+    /// Returns the position of the term in the document, counted in words from zero.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Inverted index of documents, used for finding the documents that contain
+/// some words and where in the documents the words are.
+///
+/// Documents are split into words on the word bounds of Unicode with the 'unicode'
+/// feature, and on anything that isn't a letter or a digit without it. Words are
+/// lowercased and kept as terms in a data trie, with a posting for every occurrence.
+/// Queries are lowercased the same way.
+#[derive(Debug, Default)]
+pub struct Index {
+    terms: DataTrie<Posting>,
+    documents: BTreeMap<usize, Vec<String>>,
+}
+
+impl Index {
+    /// Returns a new instance of the index.
+    pub fn new() -> Self {
+        Index {
+            terms: DataTrie::new(),
+            documents: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a document to the index under 'doc_id', with a posting for every word of 'text'.
+    /// A document that was already added under the same identifier is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "The quick brown fox.");
+    /// index.add_document(2, "The lazy dog.");
+    /// assert_eq!(vec![1, 2], index.search_prefix("the"));
+    ///
+    /// index.add_document(2, "A lazy dog.");
+    /// assert_eq!(vec![1], index.search_prefix("the"));
+    /// ```
+    pub fn add_document(&mut self, doc_id: usize, text: &str) {
+        self.remove_document(doc_id);
+
+        let mut document_terms = Vec::new();
+
+        for (position, (_, word)) in word_tokens(text).into_iter().enumerate() {
+            let term = to_term(word);
+            self.terms.insert(&term, Posting { doc_id, position });
+            document_terms.push(term);
+        }
+
+        document_terms.sort_unstable();
+        document_terms.dedup();
+        self.documents.insert(doc_id, document_terms);
+    }
+
+    /// Removes a document and all of its postings from the index. Terms left
+    /// without postings are removed as well. Returns false if no document
+    /// was added under 'doc_id'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "apples and pears");
+    /// index.add_document(2, "apples");
+    ///
+    /// assert!(index.remove_document(1));
+    /// assert!(!index.remove_document(1));
+    /// assert_eq!(vec![2], index.search_prefix("apple"));
+    /// assert!(index.search_prefix("pear").is_empty());
+    /// ```
+    pub fn remove_document(&mut self, doc_id: usize) -> bool {
+        let Some(document_terms) = self.documents.remove(&doc_id) else {
+            return false;
+        };
+
+        for term in document_terms {
+            self.terms
+                .remove_data_where(&term, |posting| posting.doc_id == doc_id, true);
+        }
+
+        true
+    }
+
+    /// Returns the identifiers of the documents that contain a word beginning with 'prefix',
+    /// ranked by the number of such words in them. Documents with as many words
+    /// are ordered by their identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "Reading is fun.");
+    /// index.add_document(2, "Read, reread and read again.");
+    /// index.add_document(3, "Writing is fun.");
+    ///
+    /// assert_eq!(vec![2, 1], index.search_prefix("read"));
+    /// assert!(index.search_prefix("math").is_empty());
+    /// ```
+    pub fn search_prefix(&self, prefix: &str) -> Vec<usize> {
+        let mut occurrences = BTreeMap::<usize, usize>::new();
+
+        for posting in self
+            .terms
+            .get_data(&to_term(prefix), true)
+            .unwrap_or_default()
+        {
+            *occurrences.entry(posting.doc_id).or_default() += 1;
+        }
+
+        let mut ranked = occurrences.into_iter().collect::<Vec<_>>();
+        // The sort is stable, so the identifiers stay ordered among equal counts.
+        ranked.sort_by(|(_, count), (_, other_count)| other_count.cmp(count));
+
+        ranked.into_iter().map(|(doc_id, _)| doc_id).collect()
+    }
+
+    /// Returns all postings of a term, ordered by document and then by position.
+    /// If the term is in no document, an empty vector is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "to be or not to be");
+    ///
+    /// let found_positions = index
+    ///     .postings("be")
+    ///     .iter()
+    ///     .map(|posting| (posting.doc_id(), posting.position()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec![(1, 1), (1, 5)], found_positions);
+    /// ```
+    pub fn postings(&self, term: &str) -> Vec<Posting> {
+        let mut postings = self
+            .terms
+            .get_data(&to_term(term), false)
+            .unwrap_or_default()
+            .into_iter()
+            .copied()
+            .collect::<Vec<_>>();
+
+        postings.sort_unstable_by_key(|posting| (posting.doc_id, posting.position));
+        postings
+    }
+
+    /// Returns the number of times a term occurs in a document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "To be or not to be.");
+    /// assert_eq!(2, index.term_frequency("to", 1));
+    /// assert_eq!(0, index.term_frequency("to", 2));
+    /// ```
+    pub fn term_frequency(&self, term: &str, doc_id: usize) -> usize {
+        self.terms
+            .get_data(&to_term(term), false)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|posting| posting.doc_id == doc_id)
+            .count()
+    }
+
+    /// Returns the number of documents a term occurs in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "to be or not to be");
+    /// index.add_document(2, "to do");
+    /// assert_eq!(2, index.document_frequency("to"));
+    /// assert_eq!(1, index.document_frequency("be"));
+    /// ```
+    pub fn document_frequency(&self, term: &str) -> usize {
+        let mut doc_ids = self
+            .postings(term)
+            .into_iter()
+            .map(|posting| posting.doc_id)
+            .collect::<Vec<_>>();
+
+        doc_ids.dedup();
+        doc_ids.len()
+    }
+
+    /// Returns the number of times a term occurs across all documents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "to be or not to be");
+    /// index.add_document(2, "to do");
+    /// assert_eq!(3, index.collection_frequency("to"));
+    /// ```
+    pub fn collection_frequency(&self, term: &str) -> usize {
+        self.terms
+            .get_data(&to_term(term), false)
+            .map_or(0, |postings| postings.len())
+    }
+
+    /// Returns true if a document was added under 'doc_id'.
+    pub fn contains_document(&self, doc_id: usize) -> bool {
+        self.documents.contains_key(&doc_id)
+    }
+
+    /// Returns the number of documents in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "first");
+    /// index.add_document(2, "second");
+    /// assert_eq!(2, index.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Returns true if no documents are in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "first");
+    /// index.remove_document(1);
+    /// assert!(index.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Removes all documents from the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Index;
+    /// let mut index = Index::new();
+    ///
+    /// index.add_document(1, "first");
+    /// index.clear();
+    /// assert!(index.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.terms.clear();
+        self.documents.clear();
+    }
+}
+
+/// Function returns the term a word is indexed and searched by.
+fn to_term(word: &str) -> String {
+    get_characters(&word.to_lowercase()).collect()
+}
@@ -8,9 +8,10 @@
 //! **Basic Trie** is implemented as a tree where each node holds a single character
 //! that could point at any other character thus allowing insertion of arbitrary words.
 //!
-//! #### There are two major implementations:
+//! #### There are three major implementations:
 //! - Trie where words are inserted with nothing attached to them
 //! - Data Trie where each word has a corresponding vector of data attached to it
+//! - Counting Trie where each word keeps the number of times it was inserted
 //!
 //! Regular tries are often used for word lookups and prefix matching, and data tries are
 //! often used for finding all data that is connected to some prefix.
@@ -23,26 +24,53 @@
 //! - insertion / removal of words
 //! - fast contains check
 //! - finding words based on a prefix
+//! - ordered range queries between two words
+//! - rank of a word and word at a given rank in lexicographic order (with the 'subtree-counts' feature)
+//! - anagrams and sub-anagrams of a rack of letters, with optional blanks, for the regular Trie
+//! - finding words spelled by paths of adjacent cells in a grid of characters, for the regular Trie
 //! - longest / shortest words in the trie
+//! - structural statistics and memory usage estimates
+//! - Graphviz DOT export and indented drawing of the trie for debugging
+//! - configurable storage of node children for every trie
+//! - arena variants of Trie and Data Trie that keep every node in a single buffer
 //! - generic methods: `is_empty`, `len`, `clear`
+//! - fallible `try_` methods that return a `TrieError` instead of silently doing nothing
+//! - optional limits on word length, nodes, memory, words and data per word for Trie and Data Trie
 //! - Trie equality with `==`
 //! - Trie merging with `+` or `+=`
+//! - finding every occurrence of the trie's words inside some text (Aho-Corasick)
+//! - spell checker suggesting words by keyboard-aware edit costs and word frequency
 //!
 //! ## Data Trie features
 //! - generic type implementation for associating a word to any type, with zero trait constraints
 //! - finding data of words based on exact match or prefix
+//! - Trie Map variant holding exactly one value per word, with a standard map-like API
+//! - substring index for finding words by any fragment they contain
+//! - Phrase Trie variant keyed by whole tokens, for finding the longest known phrases in text
+//! - inverted index of documents with word positions, prefix search ranked by occurrences and term frequencies
 //!
 //! ## Optional features
-//! - Unicode support via the 'Unicode' feature with the `unicode-segmentation` and `unicode-normalization` 
-//! crates (enabled by default)
+//! - the standard library via the 'std' feature (enabled by default); without it, the crate only needs
+//!   `alloc`, and nodes with many children are kept in a `hashbrown` map
+//! - Unicode support via the 'Unicode' feature with the `unicode-segmentation` and `unicode-normalization`
+//!   crates (enabled by default)
 //! - data trie support via the 'data' feature (enabled by default)
 //! - serialization and deserialization via the 'serde' feature with the `serde` crate
+//! - exporting to and importing from finite-state transducers via the 'fst' feature with the `fst` crate
+//!   (requires 'std')
+//! - regular expression queries via the 'regex' feature with the `regex-automata` crate, pruning
+//!   the subtrees that can't match while walking the trie
+//! - cached word counts in every node via the 'subtree-counts' feature, for counting words by
+//!   prefix and ranking words by walking a single path
 //!
 //! ## Dependencies
 //! - `unicode-segmentation` (enabled by default)
 //! - `unicode-normalization` (enabled by default)
 //! - `serde` (only with 'serde' feature flag)
-//! - `fxhash`
+//! - `fst` (only with 'fst' feature flag)
+//! - `regex-automata` (only with 'regex' feature flag)
+//! - `fxhash` (only with 'std' feature flag)
+//! - `hashbrown` (only used without 'std' feature flag)
 //! - `thin-vec`
 //!
 //! ## License
@@ -89,33 +117,61 @@
 //!
 //! ## Changelog
 //! - **2.1.0** - Child nodes are now represented differently based on the number of children. Faster
-//! processing and less memory usage. Using STD's `char` type to represent everything. 2024. edition.
+//!   processing and less memory usage. Using STD's `char` type to represent everything. 2024. edition.
 //! - **2.0.0** - Major redesign: increased memory efficiency for the regular Trie (used to be Dataless Trie);
-//! Changed API names to better match the standard library; splitting the two implementations code-wise thus
-//! fixing the documentation not rendering bug.
+//!   Changed API names to better match the standard library; splitting the two implementations code-wise thus
+//!   fixing the documentation not rendering bug.
 //! - **1.2.3** – Adding dependencies for even more memory layout optimizations.
 //! - **1.2.2** – More memory optimizations with Box.
 //! - **1.2.1** – Memory performance upgrade with Box. Mutable data retrieval.
 //! - **1.2.0** – Equality and addition operators support between
-//! same Trie types via `==`, `+` and `+=`.
+//!   same Trie types via `==`, `+` and `+=`.
 //! - **1.1.1** – Adding `FxHashMap` dependency for boosted performance.
 //! - **1.1.0** – Serialization with the `serde` crate and the 'serde' feature.
 //! - **1.0.3** – Optimization of `number_of_words()`. Removing lifetime requirements
-//! for word insertion for much better flexibility at the same logical memory cost.
+//!   for word insertion for much better flexibility at the same logical memory cost.
 //! - **1.0.2** – Bug fixes.
 //! - **1.0.1** – `insert_no_data()` for `DataTrie`. Bugfixes.
 //! - **1.0.0** – Separation of `DataTrie` and `DatalessTrie`. Optimizing
-//! performance for `DatalessTrie`. Incompatible with older versions.
+//!   performance for `DatalessTrie`. Incompatible with older versions.
 //! - **<1.0.0** – Simple `Trie` with data and base features.
 //!
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 mod child_storage;
+mod error;
+mod grid;
+#[cfg(feature = "data")]
+mod index;
+mod limits;
+mod matcher;
+#[cfg(feature = "regex")]
+mod regex;
+mod render;
+mod spell_checker;
+mod stats;
+#[cfg(feature = "data")]
+mod substring_index;
 mod trie;
 mod trie_node;
 
 #[cfg(feature = "data")]
-pub use trie::DataTrie;
+pub use index::{Index, Posting};
+#[cfg(feature = "data")]
+pub use substring_index::SubstringIndex;
+#[cfg(feature = "data")]
+pub use trie::{ArenaDataTrie, DataTrie, PhraseTrie, Tokenizer, TrieMap};
 
-pub use trie::Trie;
+pub use child_storage::StorageLayout;
+pub use error::TrieError;
+pub use grid::Adjacency;
+pub use limits::TrieLimits;
+pub use matcher::{Match, Matcher};
+pub use spell_checker::{EditCosts, SpellChecker, Suggestion};
+pub use stats::TrieStats;
+pub use trie::{ArenaTrie, CountingTrie, Trie};
 
 // Tests which are the same for both implementations,
 // Regular is used for less verbose code.
@@ -329,6 +385,448 @@ mod general_trie_tests {
         trie.insert("aaa");
         assert_eq!(trie.get_shortest(), vec![String::from("дд")]);
     }
+
+    #[test]
+    fn range_sorted() {
+        let mut trie = Trie::new();
+        for word in ["b", "ab", "a", "abc", "c", "ba", "aa", ""] {
+            trie.insert(word);
+        }
+
+        let found_words = trie.range(..).collect::<Vec<_>>();
+        assert_eq!(
+            vec!["", "a", "aa", "ab", "abc", "b", "ba", "c"],
+            found_words
+        );
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut trie = Trie::new();
+        for word in ["a", "ab", "abc", "abd", "b", "ba", "c"] {
+            trie.insert(word);
+        }
+
+        assert_eq!(
+            vec!["ab", "abc", "abd", "b"],
+            trie.range("ab".."ba").collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["ab", "abc", "abd", "b", "ba"],
+            trie.range("ab"..="ba").collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["a", "ab"], trie.range(.."abc").collect::<Vec<_>>());
+        assert_eq!(
+            vec!["abc", "abd", "b", "ba", "c"],
+            trie.range("abb"..).collect::<Vec<_>>()
+        );
+        assert!(trie.range("d"..).next().is_none());
+        assert!(trie.range("b".."b").next().is_none());
+    }
+
+    #[test]
+    fn range_excluded_start() {
+        use std::ops::Bound;
+
+        let mut trie = Trie::new();
+        for word in ["ab", "abc", "abd", "b"] {
+            trie.insert(word);
+        }
+
+        let found_words = trie
+            .range((Bound::Excluded("ab"), Bound::Unbounded))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["abc", "abd", "b"], found_words);
+    }
+
+    #[test]
+    fn range_from_take() {
+        let mut trie = Trie::new();
+        for word in ["kit", "kite", "kites", "kitten", "kiwi", "lemon"] {
+            trie.insert(word);
+        }
+
+        assert_eq!(
+            vec!["kite", "kites", "kitten"],
+            trie.range_from("kite").take(3).collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["lemon"], trie.range_from("kz").collect::<Vec<_>>());
+        assert_eq!(6, trie.range_from("").count());
+    }
+
+    #[test]
+    fn range_large_node() {
+        let mut trie = Trie::new();
+        for character in ('a'..='z').rev().chain('A'..='Z') {
+            trie.insert(&character.to_string());
+        }
+
+        let found_words = trie.range("X".."c").collect::<Vec<_>>();
+        assert_eq!(vec!["X", "Y", "Z", "a", "b"], found_words);
+    }
+
+    #[cfg(feature = "subtree-counts")]
+    fn assert_rank_select(trie: &Trie) {
+        let mut words = trie.get_all();
+        words.sort();
+
+        for (position, word) in words.iter().enumerate() {
+            assert_eq!(Some(position), trie.rank(word));
+            assert_eq!(Some(word), trie.select(position).as_ref());
+        }
+
+        assert_eq!(None, trie.select(words.len()));
+    }
+
+    #[cfg(feature = "subtree-counts")]
+    #[test]
+    fn rank_select_sorted() {
+        let mut trie = Trie::new();
+        for word in ["b", "ab", "a", "abc", "c", "ba", "aa", ""] {
+            trie.insert(word);
+        }
+
+        assert_rank_select(&trie);
+        assert_eq!(Some(0), trie.rank(""));
+        assert_eq!(Some(4), trie.rank("abc"));
+        assert_eq!(None, trie.rank("abd"));
+        assert_eq!(None, trie.rank("bb"));
+        assert_eq!(Some(String::from("ba")), trie.select(6));
+    }
+
+    #[cfg(feature = "subtree-counts")]
+    #[test]
+    fn rank_select_after_changes() {
+        let mut trie = Trie::new();
+        for word in ["word", "wording", "words", "world", "sword", "swords"] {
+            trie.insert(word);
+        }
+
+        trie.insert("word");
+        assert_rank_select(&trie);
+
+        trie.remove("word");
+        trie.remove("notfound");
+        assert_rank_select(&trie);
+        assert_eq!(None, trie.rank("word"));
+
+        trie.remove_prefix("sword");
+        assert_rank_select(&trie);
+        assert_eq!(Some(0), trie.rank("sword"));
+
+        let mut other = Trie::new();
+        for word in ["word", "world", "worlds", "a"] {
+            other.insert(word);
+        }
+
+        trie += other;
+        assert_rank_select(&trie);
+        assert_eq!(Some(String::from("worlds")), trie.select(6));
+
+        trie.clear();
+        assert_eq!(None, trie.select(0));
+    }
+
+    #[test]
+    fn remove_empty_word_not_found() {
+        let mut trie = Trie::new();
+        trie.remove("");
+        assert_eq!(0, trie.len());
+
+        trie.insert("");
+        trie.insert("a");
+        trie.remove("");
+        assert_eq!(1, trie.len());
+        assert!(!trie.contains(""));
+        #[cfg(feature = "subtree-counts")]
+        assert_eq!(Some(0), trie.rank("a"));
+    }
+
+    #[test]
+    fn len_prefix_after_changes() {
+        let mut trie = Trie::new();
+        for word in ["word", "wording", "words", "world", "sword", "swords"] {
+            trie.insert(word);
+        }
+
+        trie.remove("wording");
+        trie.remove_prefix("sword");
+
+        let mut other = Trie::new();
+        other.insert("wordy");
+        other.insert("work");
+        trie += other;
+
+        for prefix in ["", "w", "wor", "word", "s", "sword"] {
+            let found_words = trie.get(prefix).unwrap();
+            let prefix_is_word = trie.contains(prefix) as usize;
+            assert_eq!(found_words.len() - prefix_is_word, trie.len_prefix(prefix));
+        }
+
+        assert_eq!(5, trie.len_prefix("w"));
+    }
+
+    #[test]
+    fn stats_empty() {
+        let stats = Trie::new().stats();
+
+        assert_eq!(1, stats.node_count);
+        assert_eq!(0, stats.word_count);
+        assert_eq!(0.0, stats.average_depth);
+        assert_eq!(vec![1], stats.branching_histogram);
+        assert_eq!(1, stats.empty_storage_nodes);
+        assert_eq!(0, stats.heap_bytes);
+    }
+
+    #[test]
+    fn stats_storage_variants() {
+        let mut trie = Trie::new();
+        for character in ('a'..='z').chain('A'..='Z') {
+            trie.insert(&format!("{character}x"));
+        }
+        trie.insert("ab");
+
+        let stats = trie.stats();
+        assert_eq!(1 + 52 * 2 + 1, stats.node_count);
+        assert_eq!(53, stats.word_count);
+        assert_eq!(2, stats.max_depth);
+        assert_eq!(2.0, stats.average_depth);
+        assert_eq!(1, stats.dense_ascii_storage_nodes);
+        assert_eq!(0, stats.large_storage_nodes);
+        assert_eq!(52, stats.small_storage_nodes);
+        assert_eq!(53, stats.empty_storage_nodes);
+        assert_eq!(53, stats.branching_histogram[0]);
+        assert_eq!(51, stats.branching_histogram[1]);
+        assert_eq!(1, stats.branching_histogram[2]);
+        assert_eq!(1, stats.branching_histogram[52]);
+        assert!(stats.heap_bytes > 0);
+    }
+
+    #[test]
+    fn display_tree_nested() {
+        let mut trie = Trie::new();
+        for word in ["", "ab", "abc", "ad", "b"] {
+            trie.insert(word);
+        }
+
+        let correct = "\
+(root) *
+├── a
+│   ├── b *
+│   │   └── c *
+│   └── d *
+└── b *
+";
+        assert_eq!(correct, trie.display_tree(None, None).to_string());
+    }
+
+    #[test]
+    fn display_tree_limits() {
+        let mut trie = Trie::new();
+        for word in ["abc", "ad", "b"] {
+            trie.insert(word);
+        }
+
+        let correct = "\
+(root)
+├── a
+│   ├── b
+│   │   └── ...
+│   └── d *
+└── b *
+";
+        assert_eq!(correct, trie.display_tree(Some(2), None).to_string());
+
+        let correct = "\
+(root)
+├── a
+│   ├── b
+│   │   └── ...
+";
+        assert_eq!(correct, trie.display_tree(None, Some(2)).to_string());
+        assert_eq!(
+            "(root)\n└── ...\n",
+            trie.display_tree(Some(0), None).to_string()
+        );
+        assert_eq!(
+            "(root)\n",
+            Trie::new().display_tree(Some(0), None).to_string()
+        );
+    }
+
+    #[test]
+    fn to_dot_escapes_labels() {
+        let mut trie = Trie::new();
+        trie.insert("\"");
+        trie.insert("\\");
+
+        let correct = "\
+digraph trie {
+    node [shape=circle, label=\"\"];
+    n0 [shape=circle, label=\"\"];
+    n0 -> n1 [label=\"\\\"\"];
+    n0 -> n2 [label=\"\\\\\"];
+    n1 [shape=doublecircle, label=\"\"];
+    n2 [shape=doublecircle, label=\"\"];
+}
+";
+        assert_eq!(correct, trie.to_dot());
+    }
+
+    #[test]
+    fn layout_promotion_and_demotion() {
+        use crate::StorageLayout;
+
+        let layout = StorageLayout {
+            promotion_threshold: 4,
+            sorted_small: false,
+            dense_ascii: false,
+        };
+        let mut trie = Trie::with_layout(layout);
+        for word in ["a", "b", "c", "d"] {
+            trie.insert(word);
+        }
+        assert_eq!(1, trie.stats().small_storage_nodes);
+
+        trie.insert("e");
+        assert_eq!(1, trie.stats().large_storage_nodes);
+
+        trie.remove("e");
+        trie.remove("d");
+        assert_eq!(1, trie.stats().large_storage_nodes);
+
+        trie.remove("c");
+        let stats = trie.stats();
+        assert_eq!(0, stats.large_storage_nodes);
+        assert_eq!(1, stats.small_storage_nodes);
+        assert!(trie.contains("a"));
+        assert!(trie.contains("b"));
+    }
+
+    #[test]
+    fn layout_sorted_small() {
+        use crate::StorageLayout;
+
+        let layout = StorageLayout {
+            promotion_threshold: 8,
+            sorted_small: true,
+            dense_ascii: false,
+        };
+        let mut trie = Trie::with_layout(layout);
+        let words = ["m", "z", "a", "q", "b", "mz", "ma", "y"];
+        for word in words {
+            trie.insert(word);
+        }
+
+        let stats = trie.stats();
+        assert_eq!(2, stats.sorted_small_storage_nodes);
+        assert_eq!(0, stats.small_storage_nodes);
+
+        for word in words {
+            assert!(trie.contains(word));
+        }
+        assert!(!trie.contains("c"));
+
+        for word in ["c", "d", "e"] {
+            trie.insert(word);
+        }
+        assert_eq!(1, trie.stats().large_storage_nodes);
+
+        for word in ["z", "y", "q", "e", "d", "c"] {
+            trie.remove(word);
+        }
+        assert_eq!(2, trie.stats().sorted_small_storage_nodes);
+        assert_eq!(
+            vec!["a", "b", "m", "ma", "mz"],
+            trie.range(..).collect::<Vec<_>>()
+        );
+
+        let mut correct = Trie::new();
+        for word in ["mz", "ma", "m", "b", "a"] {
+            correct.insert(word);
+        }
+        assert_eq!(correct, trie);
+    }
+
+    #[test]
+    fn layout_merge() {
+        use crate::StorageLayout;
+
+        let layout = StorageLayout {
+            promotion_threshold: 2,
+            sorted_small: true,
+            dense_ascii: false,
+        };
+        let mut trie = Trie::with_layout(layout);
+        trie.insert("a");
+        trie.insert("b");
+
+        let mut other = Trie::new();
+        other.insert("c");
+        other.insert("ab");
+
+        trie += other;
+        assert_eq!(layout, trie.layout());
+        assert_eq!(4, trie.len());
+        assert_eq!(1, trie.stats().large_storage_nodes);
+        assert!(trie.contains("ab"));
+    }
+
+    #[test]
+    fn layout_dense_ascii() {
+        let mut trie = Trie::new();
+        for word in ["d", "b", "e", "a"] {
+            trie.insert(word);
+        }
+        assert_eq!(1, trie.stats().small_storage_nodes);
+
+        trie.insert("c");
+        let stats = trie.stats();
+        assert_eq!(1, stats.dense_ascii_storage_nodes);
+        assert_eq!(0, stats.small_storage_nodes);
+        assert_eq!(
+            vec!["a", "b", "c", "d", "e"],
+            trie.range(..).collect::<Vec<_>>()
+        );
+
+        trie.insert("ab");
+        trie.insert("\u{7f}");
+        assert!(trie.contains("ab"));
+        assert!(trie.contains("\u{7f}"));
+        assert!(!trie.contains("f"));
+        assert_eq!(1, trie.stats().dense_ascii_storage_nodes);
+
+        for word in ["\u{7f}", "e", "d", "c"] {
+            trie.remove(word);
+        }
+        let stats = trie.stats();
+        assert_eq!(0, stats.dense_ascii_storage_nodes);
+        assert_eq!(2, stats.small_storage_nodes);
+        assert_eq!(vec!["a", "ab", "b"], trie.range(..).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn layout_dense_ascii_non_ascii_key() {
+        let mut trie = Trie::new();
+        for word in ["a", "b", "c", "d", "e"] {
+            trie.insert(word);
+        }
+        assert_eq!(1, trie.stats().dense_ascii_storage_nodes);
+
+        trie.insert("ж");
+        let stats = trie.stats();
+        assert_eq!(0, stats.dense_ascii_storage_nodes);
+        assert_eq!(1, stats.small_storage_nodes);
+        assert_eq!(
+            vec!["a", "b", "c", "d", "e", "ж"],
+            trie.range(..).collect::<Vec<_>>()
+        );
+
+        trie.remove("ж");
+        trie.insert("f");
+        assert_eq!(1, trie.stats().dense_ascii_storage_nodes);
+        assert_eq!(6, trie.len());
+    }
 }
 
 #[cfg(feature = "data")]
@@ -876,6 +1374,257 @@ mod data_trie_tests {
         let t1_data = t1.get_data("", true).unwrap();
         assert_eq!(t1_data, Vec::from([&500; 6]));
     }
+
+    #[test]
+    fn remove_data_where() {
+        let mut trie = DataTrie::new();
+        trie.insert("word", 1);
+        trie.insert("word", 2);
+        trie.insert("word", 3);
+        trie.insert("word", 4);
+
+        let removed_data = trie.remove_data_where("word", |&data| data > 2, false);
+
+        assert_eq!(vec![3, 4], removed_data.unwrap());
+        assert_eq!(vec![&1, &2], trie.get_data("word", false).unwrap());
+    }
+
+    #[test]
+    fn remove_data_where_not_found() {
+        let mut trie = DataTrie::new();
+        trie.insert("word", 1);
+
+        assert_eq!(None, trie.remove_data_where("wor", |_| true, true));
+        assert_eq!(None, trie.remove_data_where("notfound", |_| true, true));
+        assert_eq!(
+            Vec::<i32>::new(),
+            trie.remove_data_where("word", |_| false, true).unwrap()
+        );
+        assert!(trie.contains("word"));
+    }
+
+    #[test]
+    fn remove_data_where_keep_empty() {
+        let mut trie = DataTrie::new();
+        trie.insert("word", 1);
+
+        trie.remove_data_where("word", |_| true, false);
+
+        assert!(trie.contains("word"));
+        assert_eq!(1, trie.len());
+        assert_eq!(Vec::<&i32>::new(), trie.get_data("word", false).unwrap());
+    }
+
+    #[test]
+    fn remove_data_where_prune() {
+        let mut trie = DataTrie::new();
+        trie.insert("word", 1);
+        trie.insert("wording", 2);
+
+        trie.remove_data_where("wording", |_| true, true);
+
+        let mut correct = DataTrie::new();
+        correct.insert("word", 1);
+        assert_eq!(correct, trie);
+        assert_eq!(1, trie.len());
+    }
+
+    #[test]
+    fn remove_data_where_no_data_word() {
+        let mut trie = DataTrie::<i32>::new();
+        trie.insert_no_data("word");
+
+        trie.remove_data_where("word", |_| true, true);
+
+        assert!(trie.contains("word"));
+    }
+
+    #[test]
+    fn retain() {
+        let mut trie = DataTrie::new();
+        trie.insert("a", 1);
+        trie.insert("ab", 2);
+        trie.insert("abc", 3);
+        trie.insert("abc", 4);
+        trie.insert("b", 5);
+
+        trie.retain(|_, data| *data % 2 == 0, false);
+
+        assert_eq!(4, trie.len());
+        let mut found_data = trie.get_data("", true).unwrap();
+        found_data.sort();
+        assert_eq!(vec![&2, &4], found_data);
+    }
+
+    #[test]
+    fn retain_by_word() {
+        let mut trie = DataTrie::new();
+        trie.insert("keep", 1);
+        trie.insert("drop", 2);
+        trie.insert("dropped", 3);
+
+        trie.retain(|word, _| word.starts_with('k'), true);
+
+        let mut correct = DataTrie::new();
+        correct.insert("keep", 1);
+        assert_eq!(correct, trie);
+        assert_eq!(1, trie.len());
+    }
+
+    #[test]
+    fn retain_prune_keeps_prefix_words() {
+        let mut trie = DataTrie::new();
+        trie.insert("word", 1);
+        trie.insert("wording", 2);
+        trie.insert("words", 3);
+
+        trie.retain(|_, data| *data != 1, true);
+
+        let mut found_words = trie.get_all();
+        found_words.sort();
+        assert_eq!(
+            vec![String::from("wording"), String::from("words")],
+            found_words
+        );
+        assert_eq!(2, trie.len());
+    }
+
+    #[test]
+    fn retain_modifies_data() {
+        let mut trie = DataTrie::new();
+        trie.insert("word", 1);
+        trie.insert_no_data("nodata");
+
+        trie.retain(
+            |_, data| {
+                *data *= 10;
+                true
+            },
+            true,
+        );
+
+        assert_eq!(vec![&10], trie.get_data("word", false).unwrap());
+        assert!(trie.contains("nodata"));
+    }
+
+    #[test]
+    fn map_data() {
+        let mut trie = DataTrie::new();
+        trie.insert("a", 1);
+        trie.insert("ab", 2);
+        trie.insert("ab", 3);
+        trie.insert_no_data("abc");
+
+        let mapped_trie = trie.map_data(|word, data| word.len() * 10 + data);
+
+        let mut correct = DataTrie::new();
+        correct.insert("a", 11);
+        correct.insert("ab", 22);
+        correct.insert("ab", 23);
+        correct.insert_no_data("abc");
+
+        assert_eq!(correct, mapped_trie);
+        assert_eq!(3, mapped_trie.len());
+    }
+
+    #[test]
+    fn map_data_large_node() {
+        let mut trie = DataTrie::new();
+        for character in ('a'..='z').chain('A'..='Z') {
+            trie.insert(&character.to_string(), character);
+        }
+
+        let mapped_trie = trie.map_data(|_, data| data.to_ascii_uppercase());
+
+        assert_eq!(52, mapped_trie.len());
+        assert_eq!(vec![&'Q'], mapped_trie.get_data("q", false).unwrap());
+    }
+
+    #[test]
+    fn filter_map_data() {
+        let mut trie = DataTrie::new();
+        trie.insert("word1", "1");
+        trie.insert("word1", "one");
+        trie.insert("word2", "two");
+        trie.insert("word3", "3");
+
+        let filtered_trie = trie.filter_map_data(|_, data| data.parse::<i32>().ok());
+
+        assert_eq!(3, filtered_trie.len());
+        assert!(filtered_trie.contains("word2"));
+
+        let mut found_data = filtered_trie.get_data("word", true).unwrap();
+        found_data.sort();
+        assert_eq!(vec![&1, &3], found_data);
+    }
+
+    #[test]
+    fn range() {
+        let mut trie = DataTrie::new();
+        trie.insert("apple", 1);
+        trie.insert("apple", 2);
+        trie.insert_no_data("apricot");
+        trie.insert("banana", 3);
+
+        let found_words = trie.range("ap".."b").collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (String::from("apple"), &[1, 2][..]),
+                (String::from("apricot"), &[][..])
+            ],
+            found_words
+        );
+        assert_eq!(
+            vec![(String::from("banana"), &[3][..])],
+            trie.range_from("apricots").collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "subtree-counts")]
+    #[test]
+    fn rank_select_after_changes() {
+        let mut data_trie = DataTrie::new();
+        for (data, word) in ["b", "ab", "a", "abc", "c", "ba"].into_iter().enumerate() {
+            data_trie.insert(word, data);
+        }
+
+        data_trie.insert("ab", 10);
+        data_trie.insert_no_data("ab");
+        data_trie.insert_no_data("abd");
+        assert_eq!(Some(2), data_trie.rank("abc"));
+        assert_eq!(Some(String::from("abd")), data_trie.select(3));
+
+        data_trie.remove("abc");
+        data_trie.remove_prefix("b");
+        data_trie.retain(|_, data| *data != 0, true);
+
+        let mut words = data_trie.get_all();
+        words.sort();
+        assert_eq!(vec!["a", "ab", "abd", "c"], words);
+
+        for (position, word) in words.iter().enumerate() {
+            assert_eq!(Some(position), data_trie.rank(word));
+            assert_eq!(Some(word), data_trie.select(position).as_ref());
+        }
+
+        assert_eq!(None, data_trie.select(4));
+
+        let data_trie = data_trie.map_data(|_, data| data + 1);
+        assert_eq!(Some(3), data_trie.rank("c"));
+    }
+
+    #[test]
+    fn stats_count_data() {
+        let mut data_trie = DataTrie::new();
+        data_trie.insert_no_data("word");
+        let dataless_bytes = data_trie.stats().heap_bytes;
+
+        data_trie.insert("word", [0u64; 4]);
+        let stats = data_trie.stats();
+        assert_eq!(1, stats.word_count);
+        assert_eq!(4.0, stats.average_depth);
+        assert!(stats.heap_bytes >= dataless_bytes + 32);
+    }
 }
 
 #[cfg(test)]
@@ -1233,4 +1982,2379 @@ mod regular_trie_tests {
         correct_words.sort();
         assert_eq!(t1_words, correct_words);
     }
+
+    #[test]
+    fn remove_frees_branch() {
+        let mut trie = Trie::new();
+        trie.insert("keep");
+        trie.insert("drop");
+        trie.insert("dropped");
+
+        trie.remove("drop");
+        trie.remove("dropped");
+
+        let mut correct = Trie::new();
+        correct.insert("keep");
+        assert_eq!(correct, trie);
+    }
+}
+
+#[cfg(feature = "fst")]
+#[cfg(test)]
+mod fst_tests {
+    use crate::Trie;
+
+    #[test]
+    fn set_round_trip() {
+        let mut trie = Trie::new();
+        trie.insert("word");
+        trie.insert("word1");
+        trie.insert("apple");
+        trie.insert("banana");
+
+        let set = trie.to_fst_set().unwrap();
+        assert_eq!(4, set.len());
+
+        let imported = Trie::from_fst_set(&set).unwrap();
+        assert_eq!(trie, imported);
+    }
+
+    #[test]
+    fn set_empty() {
+        let trie = Trie::new();
+
+        let set = trie.to_fst_set().unwrap();
+        assert!(set.is_empty());
+        assert!(Trie::from_fst_set(&set).unwrap().is_empty());
+    }
+
+    #[test]
+    fn set_invalid_utf8() {
+        let set = fst::Set::from_iter(vec![vec![b'a'], vec![0xff, 0xfe]]).unwrap();
+
+        assert!(Trie::from_fst_set(&set).is_err());
+    }
+
+    #[test]
+    fn set_unicode() {
+        let mut trie = Trie::new();
+        trie.insert("čaj");
+        trie.insert("ćevapi");
+        trie.insert("cvet");
+
+        let set = trie.to_fst_set().unwrap();
+        assert!(set.contains("ćevapi"));
+
+        assert_eq!(trie, Trie::from_fst_set(&set).unwrap());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn map_round_trip() {
+        use crate::DataTrie;
+
+        let mut data_trie = DataTrie::<u32>::new();
+        data_trie.insert("word", 0);
+        data_trie.insert("word1", 1);
+        data_trie.insert("apple", 2);
+
+        let map = data_trie
+            .to_fst_map(|&data| u64::from(data), false)
+            .unwrap();
+        assert_eq!(Some(1), map.get("word1"));
+
+        let imported = DataTrie::from_fst_map(&map, u64::from).unwrap();
+        assert_eq!(vec![&2], imported.get_data("apple", false).unwrap());
+        assert_eq!(3, imported.len());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn map_into_custom_data() {
+        use crate::DataTrie;
+
+        let map = fst::Map::from_iter(vec![("apple", 3), ("pear", 300)]).unwrap();
+        let imported = DataTrie::from_fst_map(&map, |value| u8::try_from(value).ok()).unwrap();
+
+        assert_eq!(vec![&Some(3)], imported.get_data("apple", false).unwrap());
+        assert_eq!(vec![&None], imported.get_data("pear", false).unwrap());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn map_first_data_only() {
+        use crate::DataTrie;
+
+        let mut data_trie = DataTrie::<u8>::new();
+        data_trie.insert("word", 5);
+        data_trie.insert("word", 6);
+        data_trie.insert_no_data("nodata");
+
+        let map = data_trie.to_fst_map(|&data| u64::from(data), true).unwrap();
+        assert_eq!(1, map.len());
+        assert_eq!(Some(5), map.get("word"));
+        assert_eq!(None, map.get("nodata"));
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn map_data_count_mismatch() {
+        use crate::{DataTrie, TrieError};
+
+        let mut data_trie = DataTrie::<u8>::new();
+        data_trie.insert("word", 5);
+        data_trie.insert("word", 6);
+
+        let map = data_trie.to_fst_map(|&data| u64::from(data), false);
+        assert_eq!(TrieError::DataCountMismatch, map.unwrap_err());
+
+        data_trie.clear_data("word").unwrap();
+        let map = data_trie.to_fst_map(|&data| u64::from(data), false);
+        assert_eq!(TrieError::DataCountMismatch, map.unwrap_err());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn map_custom_value() {
+        use crate::DataTrie;
+
+        let mut data_trie = DataTrie::new();
+        data_trie.insert("short", String::from("ab"));
+        data_trie.insert("long", String::from("abcdef"));
+
+        let map = data_trie
+            .to_fst_map(|data| data.len() as u64, false)
+            .unwrap();
+        assert_eq!(Some(6), map.get("long"));
+        assert_eq!(Some(2), map.get("short"));
+    }
+}
+
+#[cfg(test)]
+mod matcher_tests {
+    use crate::Trie;
+
+    fn words_with_positions(
+        trie: Trie,
+        text: &str,
+        overlapping: bool,
+    ) -> Vec<(usize, usize, String)> {
+        let matcher = trie.into_matcher();
+        let found_matches = if overlapping {
+            matcher.find_overlapping(text)
+        } else {
+            matcher.find_leftmost_longest(text)
+        };
+
+        found_matches
+            .iter()
+            .map(|m| (m.start(), m.end(), m.word().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn overlapping() {
+        let mut trie = Trie::new();
+        trie.insert("a");
+        trie.insert("ab");
+        trie.insert("bab");
+        trie.insert("bc");
+        trie.insert("bca");
+        trie.insert("c");
+        trie.insert("caa");
+
+        let found = words_with_positions(trie, "abccab", true);
+
+        assert_eq!(
+            vec![
+                (0, 1, String::from("a")),
+                (0, 2, String::from("ab")),
+                (1, 3, String::from("bc")),
+                (2, 3, String::from("c")),
+                (3, 4, String::from("c")),
+                (4, 5, String::from("a")),
+                (4, 6, String::from("ab")),
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn overlapping_same_end() {
+        let mut trie = Trie::new();
+        trie.insert("she");
+        trie.insert("he");
+        trie.insert("e");
+
+        let found = words_with_positions(trie, "she", true);
+
+        assert_eq!(
+            vec![
+                (0, 3, String::from("she")),
+                (1, 3, String::from("he")),
+                (2, 3, String::from("e")),
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn leftmost_longest() {
+        let mut trie = Trie::new();
+        trie.insert("abcd");
+        trie.insert("b");
+        trie.insert("bcd");
+        trie.insert("cde");
+
+        let found = words_with_positions(trie, "xbcde abcd", false);
+
+        assert_eq!(
+            vec![(1, 4, String::from("bcd")), (6, 10, String::from("abcd"))],
+            found
+        );
+    }
+
+    #[test]
+    fn leftmost_longest_earlier_start_found_later() {
+        let mut trie = Trie::new();
+        trie.insert("bc");
+        trie.insert("abcd");
+        trie.insert("de");
+
+        let found = words_with_positions(trie, "abcde abcx", false);
+
+        assert_eq!(
+            vec![(0, 4, String::from("abcd")), (7, 9, String::from("bc"))],
+            found
+        );
+    }
+
+    #[test]
+    fn no_matches() {
+        let mut trie = Trie::new();
+        trie.insert("word");
+
+        assert!(words_with_positions(trie, "wor ord wrd", true).is_empty());
+    }
+
+    #[test]
+    fn empty_word_not_matched() {
+        let mut trie = Trie::new();
+        trie.insert("");
+        trie.insert("a");
+
+        assert_eq!(
+            vec![(1, 2, String::from("a"))],
+            words_with_positions(trie, "ba", true)
+        );
+    }
+
+    #[test]
+    fn unicode_byte_positions() {
+        let mut trie = Trie::new();
+        trie.insert("čaj");
+        trie.insert("aj");
+
+        let text = "ćao čaj";
+        let found = words_with_positions(trie, text, true);
+
+        assert_eq!(
+            vec![(5, 9, String::from("čaj")), (7, 9, String::from("aj"))],
+            found
+        );
+        assert_eq!("čaj", &text[5..9]);
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_matches() {
+        use crate::DataTrie;
+
+        let mut data_trie = DataTrie::new();
+        data_trie.insert("apple", 1);
+        data_trie.insert("apple", 2);
+        data_trie.insert("pie", 3);
+        data_trie.insert_no_data("pineapple");
+
+        let matcher = data_trie.into_matcher();
+        let found = matcher
+            .find_overlapping("pineapple pie")
+            .iter()
+            .map(|m| (m.word(), m.data().to_vec()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                ("pineapple", vec![]),
+                ("apple", vec![1, 2]),
+                ("pie", vec![3])
+            ],
+            found
+        );
+    }
+}
+
+#[cfg(feature = "data")]
+#[cfg(test)]
+mod substring_index_tests {
+    use crate::SubstringIndex;
+
+    #[test]
+    fn find_containing() {
+        let mut index = SubstringIndex::new();
+        index.insert("iphone");
+        index.insert("microphone");
+        index.insert("phone");
+        index.insert("phantom");
+
+        let mut found_words = index.find_containing("phon");
+        found_words.sort();
+        assert_eq!(vec!["iphone", "microphone", "phone"], found_words);
+
+        let mut found_words = index.find_containing("ph");
+        found_words.sort();
+        assert_eq!(
+            vec!["iphone", "microphone", "phantom", "phone"],
+            found_words
+        );
+
+        assert!(index.find_containing("xyz").is_empty());
+    }
+
+    #[test]
+    fn repeated_fragment() {
+        let mut index = SubstringIndex::new();
+        index.insert("banana");
+
+        assert_eq!(vec!["banana"], index.find_containing("an"));
+        assert_eq!(vec!["banana"], index.find_containing("a"));
+    }
+
+    #[test]
+    fn same_word_twice() {
+        let mut index = SubstringIndex::new();
+        index.insert("twice");
+        index.insert("twice");
+
+        assert_eq!(1, index.len());
+        assert_eq!(vec!["twice"], index.find_containing("wic"));
+    }
+
+    #[test]
+    fn all_words() {
+        let mut index = SubstringIndex::new();
+        index.insert("a");
+        index.insert("b");
+        index.insert("");
+
+        let mut found_words = index.find_containing("");
+        found_words.sort();
+        assert_eq!(vec!["", "a", "b"], found_words);
+    }
+
+    #[test]
+    fn remove() {
+        let mut index = SubstringIndex::new();
+        index.insert("phone");
+        index.insert("iphone");
+        index.insert("microphone");
+
+        index.remove("phone");
+        assert!(!index.contains("phone"));
+        assert_eq!(2, index.len());
+
+        let mut found_words = index.find_containing("phone");
+        found_words.sort();
+        assert_eq!(vec!["iphone", "microphone"], found_words);
+
+        index.remove("notfound");
+        assert_eq!(2, index.len());
+
+        index.remove("iphone");
+        index.remove("microphone");
+        assert!(index.is_empty());
+        assert!(index.find_containing("").is_empty());
+    }
+
+    #[test]
+    fn remove_and_reinsert() {
+        let mut index = SubstringIndex::new();
+        index.insert("first");
+        index.insert("second");
+
+        index.remove("first");
+        index.insert("third");
+
+        assert_eq!(vec!["third"], index.find_containing("ir"));
+        assert!(index.contains("second"));
+        assert!(index.contains("third"));
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn unicode() {
+        let mut index = SubstringIndex::new();
+        index.insert("čokolada");
+        index.insert("ćevapčići");
+
+        assert_eq!(vec!["čokolada"], index.find_containing("kol"));
+        assert_eq!(vec!["ćevapčići"], index.find_containing("pči"));
+    }
+}
+
+#[cfg(test)]
+mod counting_trie_tests {
+    use crate::CountingTrie;
+
+    #[test]
+    fn insert_and_count() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert("word");
+        trie.insert("word");
+        trie.insert("wording");
+        trie.insert_n("other", 4);
+
+        assert_eq!(2, trie.count("word"));
+        assert_eq!(1, trie.count("wording"));
+        assert_eq!(4, trie.count("other"));
+        assert_eq!(0, trie.count("wor"));
+        assert_eq!(3, trie.len());
+        assert_eq!(7, trie.total_count());
+    }
+
+    #[test]
+    fn count_prefix() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert_n("a", 2);
+        trie.insert_n("ab", 3);
+        trie.insert_n("abc", 4);
+        trie.insert_n("b", 5);
+
+        assert_eq!(14, trie.count_prefix(""));
+        assert_eq!(7, trie.count_prefix("a"));
+        assert_eq!(4, trie.count_prefix("ab"));
+        assert_eq!(0, trie.count_prefix("abc"));
+        assert_eq!(0, trie.count_prefix("notfound"));
+    }
+
+    #[test]
+    fn remove_decrements() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert_n("word", 2);
+        trie.insert("wording");
+
+        trie.remove("word");
+        assert_eq!(1, trie.count("word"));
+        assert_eq!(2, trie.total_count());
+        assert_eq!(1, trie.count_prefix("word"));
+
+        trie.remove("word");
+        assert!(!trie.contains("word"));
+        assert!(trie.contains("wording"));
+        assert_eq!(1, trie.len());
+        assert_eq!(1, trie.count_prefix("word"));
+
+        trie.remove("word");
+        assert_eq!(1, trie.total_count());
+    }
+
+    #[test]
+    fn remove_frees_branches() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert("word");
+        trie.insert_n("wording", 3);
+
+        assert_eq!(3, trie.remove_all("wording"));
+        assert_eq!(vec![(String::from("word"), 1)], trie.get_all());
+
+        let mut correct = CountingTrie::new();
+        correct.insert("word");
+        assert_eq!(correct, trie);
+
+        trie.remove("word");
+        assert!(trie.is_empty());
+        assert_eq!(CountingTrie::new(), trie);
+    }
+
+    #[test]
+    fn most_frequent() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert_n("the", 50);
+        trie.insert_n("a", 30);
+        trie.insert_n("of", 30);
+        trie.insert_n("trie", 2);
+        trie.insert_n("tree", 1);
+
+        assert_eq!(
+            vec![
+                (String::from("the"), 50),
+                (String::from("a"), 30),
+                (String::from("of"), 30),
+            ],
+            trie.most_frequent(3)
+        );
+        assert_eq!(5, trie.most_frequent(10).len());
+        assert!(trie.most_frequent(0).is_empty());
+    }
+
+    #[test]
+    fn most_frequent_ties() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert("c");
+        trie.insert("b");
+        trie.insert("a");
+
+        assert_eq!(
+            vec![(String::from("a"), 1), (String::from("b"), 1)],
+            trie.most_frequent(2)
+        );
+    }
+
+    #[test]
+    fn clear() {
+        let mut trie = CountingTrie::new();
+
+        trie.insert_n("word", 3);
+        trie.clear();
+
+        assert!(trie.is_empty());
+        assert_eq!(0, trie.total_count());
+        assert_eq!(0, trie.count("word"));
+    }
+
+    #[test]
+    fn with_layout() {
+        use crate::StorageLayout;
+
+        let layout = StorageLayout {
+            promotion_threshold: 1,
+            sorted_small: true,
+            dense_ascii: false,
+        };
+        let mut trie = CountingTrie::with_layout(layout);
+
+        trie.insert_n("bb", 2);
+        trie.insert("ab");
+        trie.insert("ac");
+        trie.remove("ab");
+
+        assert_eq!(layout, trie.layout());
+        assert_eq!(2, trie.count("bb"));
+        assert_eq!(1, trie.count("ac"));
+        assert_eq!(0, trie.count("ab"));
+        assert_eq!(3, trie.total_count());
+    }
+}
+
+#[cfg(feature = "data")]
+#[cfg(test)]
+mod trie_map_tests {
+    use crate::TrieMap;
+
+    #[test]
+    fn insert_replaces() {
+        let mut trie_map = TrieMap::new();
+
+        assert_eq!(None, trie_map.insert("word", "first"));
+        assert_eq!(Some("first"), trie_map.insert("word", "second"));
+        assert_eq!(Some(&"second"), trie_map.get("word"));
+        assert_eq!(1, trie_map.len());
+    }
+
+    #[test]
+    fn get_not_found() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("word", 1);
+
+        assert_eq!(None, trie_map.get("wor"));
+        assert_eq!(None, trie_map.get("words"));
+        assert_eq!(None, trie_map.get_mut("notfound"));
+        assert!(!trie_map.contains_key("wor"));
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("word", vec![1]);
+
+        trie_map.get_mut("word").unwrap().push(2);
+        assert_eq!(Some(&vec![1, 2]), trie_map.get("word"));
+    }
+
+    #[test]
+    fn remove_prefix_word() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("a", 1);
+        trie_map.insert("ab", 2);
+        trie_map.insert("abc", 3);
+
+        assert_eq!(Some(2), trie_map.remove("ab"));
+        assert_eq!(None, trie_map.remove("ab"));
+        assert_eq!(2, trie_map.len());
+
+        let mut found_entries = trie_map.get_all();
+        found_entries.sort();
+        assert_eq!(
+            vec![(String::from("a"), &1), (String::from("abc"), &3)],
+            found_entries
+        );
+    }
+
+    #[test]
+    fn remove_frees_branches() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("word", 1);
+        trie_map.insert("wording", 2);
+
+        assert_eq!(Some(2), trie_map.remove("wording"));
+
+        let mut correct = TrieMap::new();
+        correct.insert("word", 1);
+        assert_eq!(correct, trie_map);
+
+        assert_eq!(Some(1), trie_map.remove("word"));
+        assert_eq!(TrieMap::new(), trie_map);
+        assert!(trie_map.is_empty());
+    }
+
+    #[test]
+    fn remove_not_a_word() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("word", 1);
+
+        assert_eq!(None, trie_map.remove("wor"));
+        assert_eq!(None, trie_map.remove("notfound"));
+        assert_eq!(1, trie_map.len());
+        assert_eq!(Some(&1), trie_map.get("word"));
+    }
+
+    #[test]
+    fn get_prefix() {
+        let trie_map = [("apple", 1), ("apricot", 2), ("banana", 3)]
+            .into_iter()
+            .collect::<TrieMap<_>>();
+
+        let mut found_entries = trie_map.get_prefix("ap").unwrap();
+        found_entries.sort();
+        assert_eq!(
+            vec![(String::from("apple"), &1), (String::from("apricot"), &2)],
+            found_entries
+        );
+        assert_eq!(None, trie_map.get_prefix("c"));
+    }
+
+    #[test]
+    fn clear() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("word", 1);
+
+        trie_map.clear();
+        assert!(trie_map.is_empty());
+        assert!(trie_map.get_all().is_empty());
+    }
+
+    #[test]
+    fn with_layout() {
+        use crate::StorageLayout;
+
+        let layout = StorageLayout {
+            promotion_threshold: 1,
+            sorted_small: true,
+            dense_ascii: false,
+        };
+        let mut trie_map = TrieMap::with_layout(layout);
+
+        trie_map.insert("bb", 1);
+        trie_map.insert("ab", 2);
+        trie_map.insert("ac", 3);
+
+        assert_eq!(layout, trie_map.layout());
+        assert_eq!(Some(2), trie_map.remove("ab"));
+        assert_eq!(Some(&3), trie_map.get("ac"));
+        assert_eq!(Some(&1), trie_map.get("bb"));
+        assert_eq!(2, trie_map.len());
+    }
+
+    #[test]
+    fn iter_keys_values() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("b", 1);
+        trie_map.insert("ab", 2);
+        trie_map.insert("", 3);
+        trie_map.insert("abc", 4);
+        trie_map.remove("ab");
+
+        assert_eq!(
+            vec![
+                (String::new(), &3),
+                (String::from("abc"), &4),
+                (String::from("b"), &1)
+            ],
+            trie_map.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["", "abc", "b"], trie_map.keys().collect::<Vec<_>>());
+        assert_eq!(vec![&3, &4, &1], trie_map.values().collect::<Vec<_>>());
+        assert_eq!(0, TrieMap::<i32>::new().iter().count());
+    }
+
+    #[test]
+    fn index() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("word", 1);
+        trie_map.insert("words", 2);
+
+        assert_eq!(1, trie_map["word"]);
+        assert_eq!(2, trie_map["words"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "word not found in the trie map")]
+    fn index_not_found() {
+        let mut trie_map = TrieMap::new();
+        trie_map.insert("words", 1);
+
+        let _ = trie_map["word"];
+    }
+}
+
+#[cfg(test)]
+mod arena_trie_tests {
+    use crate::{ArenaTrie, Trie};
+
+    const WORDS: [&str; 12] = [
+        "eat",
+        "eating",
+        "eats",
+        "ea",
+        "wizard",
+        "wizardry",
+        "wiz",
+        "a",
+        "",
+        "čokolada",
+        "čaj",
+        "zebra",
+    ];
+
+    #[test]
+    fn same_words_as_trie() {
+        let mut trie = Trie::new();
+        let mut arena_trie = ArenaTrie::new();
+        for word in WORDS {
+            trie.insert(word);
+            arena_trie.insert(word);
+        }
+
+        let mut all_words = trie.get_all();
+        all_words.sort();
+        assert_eq!(all_words, arena_trie.get_all());
+        assert_eq!(trie.len(), arena_trie.len());
+        assert_eq!(trie.len_prefix("ea"), arena_trie.len_prefix("ea"));
+        assert_eq!(vec!["wizardry", "čokolada"], arena_trie.get_longest());
+        assert_eq!(vec![""], arena_trie.get_shortest());
+        assert_eq!(None, arena_trie.get("x"));
+
+        for word in ["eat", "wizard", "", "čaj", "notfound"] {
+            trie.remove(word);
+            arena_trie.remove(word);
+        }
+
+        let mut all_words = trie.get_all();
+        all_words.sort();
+        assert_eq!(all_words, arena_trie.get_all());
+        assert_eq!(trie.len(), arena_trie.len());
+        assert!(!arena_trie.contains("eat"));
+        assert!(arena_trie.contains("eating"));
+        assert!(arena_trie.contains("čokolada"));
+    }
+
+    #[test]
+    fn remove_frees_unused_paths() {
+        let mut trie = ArenaTrie::new();
+        trie.insert("word");
+        trie.insert("wording");
+
+        trie.remove("wording");
+        assert_eq!(None, trie.get("wordi"));
+        assert_eq!(vec!["word"], trie.get("w").unwrap());
+
+        trie.insert("wordy");
+        trie.insert("wax");
+        assert_eq!(vec!["wax", "word", "wordy"], trie.get_all());
+
+        trie.remove("word");
+        trie.remove("wordy");
+        trie.remove("wax");
+        assert!(trie.is_empty());
+        assert_eq!(ArenaTrie::new(), trie);
+    }
+
+    #[test]
+    fn remove_prefix() {
+        let mut trie = ArenaTrie::new();
+        for word in WORDS {
+            trie.insert(word);
+        }
+
+        trie.remove_prefix("wiz");
+        assert!(trie.contains("wiz"));
+        assert!(!trie.contains("wizard"));
+        assert_eq!(0, trie.len_prefix("wiz"));
+        assert_eq!(10, trie.len());
+
+        trie.remove_prefix("");
+        assert_eq!(vec![""], trie.get_all());
+        assert_eq!(1, trie.len());
+    }
+
+    #[test]
+    fn merge_and_equality() {
+        let mut trie_1 = ArenaTrie::new();
+        let mut trie_2 = ArenaTrie::new();
+        let mut correct = ArenaTrie::new();
+        for (i, word) in WORDS.into_iter().enumerate() {
+            if i % 2 == 0 {
+                trie_1.insert(word);
+            } else {
+                trie_2.insert(word);
+            }
+            correct.insert(word);
+        }
+        trie_2.insert("eat");
+
+        trie_1 += trie_2;
+        assert_eq!(correct, trie_1);
+        assert_eq!(WORDS.len(), trie_1.len());
+    }
+
+    #[test]
+    fn clear_and_reuse() {
+        let mut trie = ArenaTrie::with_capacity(16);
+        for word in WORDS {
+            trie.insert(word);
+        }
+
+        trie.clear();
+        assert!(trie.is_empty());
+        assert!(!trie.contains(""));
+        assert_eq!(Vec::<String>::new(), trie.get_all());
+
+        trie.insert("again");
+        assert_eq!(vec!["again"], trie.get_all());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_data() {
+        use crate::ArenaDataTrie;
+
+        let mut trie = ArenaDataTrie::new();
+        trie.insert("word", 1);
+        trie.insert("wording", 2);
+        trie.insert("word", 3);
+        trie.insert_no_data("wordy");
+
+        assert_eq!(3, trie.len());
+        assert_eq!(vec![&1, &3], trie.get_data("word", false).unwrap());
+        assert_eq!(vec![&1, &3, &2], trie.get_data("wor", true).unwrap());
+        assert_eq!(None, trie.get_data("wor", false));
+
+        for data in trie.get_data_mut("word", true).unwrap() {
+            *data *= 10;
+        }
+        assert_eq!(Some(vec![10, 30]), trie.clear_data("word"));
+        assert!(trie.contains("word"));
+
+        assert_eq!(Some(vec![20]), trie.remove_prefix("word"));
+        assert_eq!(vec!["word"], trie.get_all());
+        assert_eq!(Some(vec![]), trie.remove("word"));
+        assert!(trie.is_empty());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_mut_stays_in_subtree() {
+        use crate::ArenaDataTrie;
+
+        let mut trie = ArenaDataTrie::new();
+        trie.insert("apple", 1);
+        trie.insert("banana", 2);
+        trie.insert("apricot", 3);
+        trie.insert("band", 4);
+        trie.insert("ap", 5);
+
+        for data in trie.get_data_mut("ap", true).unwrap() {
+            *data *= 10;
+        }
+        assert_eq!(vec![&50, &10, &30], trie.get_data("ap", true).unwrap());
+        assert_eq!(vec![&2, &4], trie.get_data("ban", true).unwrap());
+
+        assert!(trie.get_data_mut("c", true).is_none());
+        assert_eq!(5, trie.get_data_mut("", true).unwrap().len());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_merge() {
+        use crate::ArenaDataTrie;
+
+        let mut trie_1 = ArenaDataTrie::new();
+        trie_1.insert("word", 1);
+        trie_1.insert("other", 2);
+
+        let mut trie_2 = ArenaDataTrie::new();
+        trie_2.insert("word", 3);
+        trie_2.insert("words", 4);
+
+        let trie_3 = trie_1 + trie_2;
+        assert_eq!(3, trie_3.len());
+        assert_eq!(vec![&2, &1, &3, &4], trie_3.get_data("", true).unwrap());
+
+        let mut correct = ArenaDataTrie::new();
+        correct.insert("other", 2);
+        correct.insert("word", 1);
+        correct.insert("word", 3);
+        correct.insert("words", 4);
+        assert_eq!(correct, trie_3);
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_eq_ignores_data_order() {
+        use crate::ArenaDataTrie;
+
+        let mut trie_1 = ArenaDataTrie::new();
+        trie_1.insert("word", 1);
+        trie_1.insert("word", 2);
+
+        let mut trie_2 = ArenaDataTrie::new();
+        trie_2.insert("word", 2);
+        trie_2.insert("word", 1);
+        assert_eq!(trie_1, trie_2);
+
+        trie_2.insert("word", 3);
+        assert_ne!(trie_1, trie_2);
+
+        let mut trie_3 = ArenaDataTrie::<i32>::new();
+        trie_3.insert_no_data("word");
+        assert_ne!(ArenaDataTrie::new(), trie_3);
+    }
+}
+
+#[cfg(test)]
+mod deep_trie_tests {
+    use crate::{CountingTrie, Trie};
+
+    const DEPTH: usize = 1_000_000;
+
+    fn long_word(character: char) -> String {
+        std::iter::repeat_n(character, DEPTH).collect()
+    }
+
+    #[test]
+    fn regular_trie_long_words() {
+        let word = long_word('a');
+        let prefix = &word[..DEPTH / 2];
+
+        let mut trie = Trie::new();
+        trie.insert(&word);
+        trie.insert(prefix);
+
+        assert!(trie.contains(&word));
+        assert_eq!(vec![word.clone()], trie.get_longest());
+        assert_eq!(vec![prefix.to_string()], trie.get_shortest());
+        assert_eq!(2, trie.get("").unwrap().len());
+        assert_eq!(1, trie.len_prefix(prefix));
+
+        let mut other = Trie::new();
+        other.insert(&word);
+        other.insert(&long_word('b'));
+        trie += other;
+        assert_eq!(3, trie.len());
+
+        let mut correct = Trie::new();
+        correct.insert(prefix);
+        correct.insert(&word);
+        correct.insert(&long_word('b'));
+        assert_eq!(correct, trie);
+
+        trie.remove(&word);
+        assert!(!trie.contains(&word));
+        assert!(trie.contains(prefix));
+        assert_ne!(correct, trie);
+
+        trie.remove_prefix("");
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn regular_trie_drop_long_word() {
+        let mut trie = Trie::new();
+        trie.insert(&long_word('a'));
+        drop(trie);
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_long_words() {
+        use crate::DataTrie;
+
+        let word = long_word('a');
+        let prefix = &word[..DEPTH / 2];
+
+        let mut trie = DataTrie::new();
+        trie.insert(&word, 1);
+        trie.insert(prefix, 2);
+
+        assert_eq!(vec![&2, &1], trie.get_data("", true).unwrap());
+        for data in trie.get_data_mut("", true).unwrap() {
+            *data *= 10;
+        }
+        assert_eq!(vec![word.clone()], trie.get_longest());
+        assert_eq!(
+            vec![prefix.to_string()],
+            trie.get("").unwrap()[..1].to_vec()
+        );
+
+        trie.retain(|_, data| *data > 10, false);
+        assert_eq!(vec![&20], trie.get_data("", true).unwrap());
+
+        let mut trie = trie.map_data(|word, data| word.len() + data);
+        assert_eq!(vec![&(DEPTH / 2 + 20)], trie.get_data("", true).unwrap());
+
+        let mut other = DataTrie::new();
+        other.insert(&word, 3);
+        trie += other;
+        assert_eq!(
+            vec![&(DEPTH / 2 + 20), &3],
+            trie.get_data("", true).unwrap()
+        );
+
+        let mut correct = DataTrie::new();
+        correct.insert(prefix, DEPTH / 2 + 20);
+        correct.insert(&word, 3);
+        assert_eq!(correct, trie);
+
+        assert_eq!(Some(vec![3]), trie.remove(&word));
+        correct.remove(prefix);
+        assert_eq!(Some(vec![3]), correct.remove_prefix(""));
+        assert_eq!(Some(vec![DEPTH / 2 + 20]), trie.remove_prefix(""));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn counting_trie_long_words() {
+        let word = long_word('a');
+
+        let mut trie = CountingTrie::new();
+        trie.insert_n(&word, 3);
+        trie.insert(&word[..DEPTH / 2]);
+
+        assert_eq!(vec![(word.clone(), 3)], trie.most_frequent(1));
+        assert_eq!(2, trie.get_all().len());
+
+        let mut correct = CountingTrie::new();
+        correct.insert(&word[..DEPTH / 2]);
+        correct.insert_n(&word, 3);
+        assert_eq!(correct, trie);
+
+        assert_eq!(3, trie.remove_all(&word));
+        assert_eq!(1, trie.len());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn trie_map_long_words() {
+        use crate::TrieMap;
+
+        let word = long_word('a');
+
+        let mut trie_map = TrieMap::new();
+        trie_map.insert(&word, 1);
+        trie_map.insert(&word[..DEPTH / 2], 2);
+
+        assert_eq!(Some(&1), trie_map.get(&word));
+        assert_eq!(2, trie_map.get_all().len());
+
+        let mut correct = TrieMap::new();
+        correct.insert(&word[..DEPTH / 2], 2);
+        correct.insert(&word, 1);
+        assert_eq!(correct, trie_map);
+
+        assert_eq!(Some(1), trie_map.remove(&word));
+        assert_eq!(None, trie_map.get(&word));
+        assert_eq!(Some(&2), trie_map.get(&word[..DEPTH / 2]));
+    }
+}
+
+#[cfg(test)]
+mod trie_error_tests {
+    use crate::{ArenaTrie, CountingTrie, Trie, TrieError};
+
+    #[test]
+    fn regular_trie_try_remove() {
+        let mut trie = Trie::new();
+        trie.insert("word");
+        trie.insert("wording");
+
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove("wordi"));
+        assert_eq!(Err(TrieError::NotFound), trie.try_remove("worse"));
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove(""));
+        assert_eq!(2, trie.len());
+
+        assert_eq!(Ok(()), trie.try_remove("word"));
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove("word"));
+        assert_eq!(Ok(()), trie.try_remove("wording"));
+        assert_eq!(Err(TrieError::NotFound), trie.try_remove("word"));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn regular_trie_try_get() {
+        let mut trie = Trie::new();
+        trie.insert("word");
+
+        assert_eq!(Ok(vec![String::from("word")]), trie.try_get(""));
+        assert_eq!(Ok(vec![String::from("word")]), trie.try_get("word"));
+        assert_eq!(Err(TrieError::NotFound), trie.try_get("words"));
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_try_methods() {
+        use crate::DataTrie;
+
+        let mut trie = DataTrie::new();
+        trie.insert_no_data("word");
+        trie.insert("wording", 1);
+        trie.insert("wording", 2);
+
+        // A word without data isn't confused with a missing word.
+        assert_eq!(None, trie.get_data("wordi", false));
+        assert_eq!(Ok(vec![]), trie.try_get_data("word", false));
+        assert_eq!(Err(TrieError::NotAWord), trie.try_get_data("wordi", false));
+        assert_eq!(Ok(vec![&1, &2]), trie.try_get_data("wordi", true));
+        assert_eq!(Err(TrieError::NotFound), trie.try_get_data("wordy", true));
+
+        assert_eq!(Err(TrieError::NotAWord), trie.try_clear_data("wordin"));
+        assert_eq!(Ok(vec![1, 2]), trie.try_clear_data("wording"));
+
+        assert_eq!(Err(TrieError::NotFound), trie.try_remove("wordy"));
+        assert_eq!(Ok(vec![]), trie.try_remove("wording"));
+        assert_eq!(Ok(vec![]), trie.try_remove("word"));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn counting_trie_try_remove() {
+        let mut trie = CountingTrie::new();
+        trie.insert_n("word", 2);
+
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove("wor"));
+        assert_eq!(Ok(()), trie.try_remove("word"));
+        assert_eq!(1, trie.count("word"));
+        assert_eq!(Ok(()), trie.try_remove("word"));
+        assert_eq!(Err(TrieError::NotFound), trie.try_remove("word"));
+    }
+
+    #[test]
+    fn arena_trie_try_methods() {
+        let mut trie = ArenaTrie::new();
+
+        assert_eq!(Ok(()), trie.try_insert("word"));
+        assert_eq!(Ok(()), trie.try_insert("word"));
+        assert_eq!(Ok(()), trie.try_insert("wording"));
+        assert_eq!(2, trie.len());
+
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove("wordin"));
+        assert_eq!(Err(TrieError::NotFound), trie.try_remove("other"));
+        assert_eq!(Ok(()), trie.try_remove("wording"));
+        assert_eq!(vec![String::from("word")], trie.get_all());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn arena_data_trie_try_methods() {
+        use crate::ArenaDataTrie;
+
+        let mut trie = ArenaDataTrie::new();
+
+        assert_eq!(Ok(()), trie.try_insert("word", 1));
+        assert_eq!(Ok(()), trie.try_insert("word", 2));
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove("wor"));
+        assert_eq!(Ok(vec![1, 2]), trie.try_remove("word"));
+        assert_eq!(Err(TrieError::NotFound), trie.try_remove("word"));
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            "no word begins with the given characters",
+            TrieError::NotFound.to_string()
+        );
+        assert_eq!(
+            "the trie can't hold any more nodes",
+            TrieError::CapacityExceeded.to_string()
+        );
+    }
+}
+
+#[cfg(test)]
+mod trie_limits_tests {
+    use crate::{Trie, TrieError, TrieLimits};
+
+    #[test]
+    fn node_count_tracking() {
+        let mut trie = Trie::new();
+        let assert_node_count = |trie: &Trie| {
+            assert_eq!(trie.stats().node_count - 1, trie.node_count());
+        };
+
+        for word in ["eat", "eating", "eats", "ear", "word", "wording", "a"] {
+            trie.insert(word);
+            assert_node_count(&trie);
+        }
+        trie.insert("eat");
+        assert_eq!(16, trie.node_count());
+
+        trie.remove("eating");
+        assert_node_count(&trie);
+        trie.remove("eat");
+        assert_node_count(&trie);
+        assert_eq!(Err(TrieError::NotAWord), trie.try_remove("wordin"));
+        trie.remove_prefix("wor");
+        assert_node_count(&trie);
+
+        let mut other = Trie::new();
+        other.insert("ears");
+        other.insert("wordy");
+        other.insert("ban");
+        trie += other;
+        assert_node_count(&trie);
+
+        let mut other = Trie::new();
+        other.insert("eats");
+        other.insert("bandit");
+        other.insert("zoo");
+        let mut trie = trie + other;
+        assert_node_count(&trie);
+
+        let mut a = Trie::new();
+        a.insert("ab");
+        a.insert("ac");
+        let mut b = Trie::new();
+        b.insert("abd");
+        b.insert("ae");
+        b.insert("xy");
+        let a_plus_b = a + b;
+        assert_eq!(7, a_plus_b.node_count());
+        assert_node_count(&a_plus_b);
+
+        trie.clear();
+        assert_eq!(0, trie.node_count());
+    }
+
+    #[test]
+    fn regular_trie_limits() {
+        let mut trie = Trie::with_limits(TrieLimits {
+            max_word_len: Some(6),
+            max_nodes: Some(8),
+            max_memory_bytes: None,
+            max_words: Some(3),
+            max_data_per_word: Some(0),
+        });
+
+        assert_eq!(Err(TrieError::WordTooLong), trie.try_insert("toolong"));
+        assert_eq!(Ok(()), trie.try_insert("word"));
+        assert_eq!(Ok(()), trie.try_insert("words"));
+        assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("other"));
+        assert_eq!(Ok(()), trie.try_insert("wor"));
+        assert_eq!(Err(TrieError::TooManyWords), trie.try_insert("wo"));
+
+        // Nothing is inserted when a limit is exceeded.
+        assert_eq!(3, trie.len());
+        assert_eq!(5, trie.node_count());
+        assert_eq!(trie.stats().node_count - 1, trie.node_count());
+
+        // Words that are already in the trie can always be inserted again.
+        trie.set_limits(TrieLimits {
+            max_nodes: Some(1),
+            max_words: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(Ok(()), trie.try_insert("words"));
+
+        trie.remove("words");
+        trie.remove("word");
+        assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("word"));
+        assert_eq!(Err(TrieError::TooManyWords), trie.try_insert("wo"));
+        trie.remove("wor");
+        assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("wo"));
+        assert_eq!(Ok(()), trie.try_insert("w"));
+    }
+
+    #[test]
+    fn memory_limit() {
+        let node_bytes = size_of::<(char, crate::trie_node::TrieDatalessNode)>();
+        let mut trie = Trie::with_limits(TrieLimits {
+            max_memory_bytes: Some(5 * node_bytes),
+            ..Default::default()
+        });
+
+        assert_eq!(Ok(()), trie.try_insert("word"));
+        assert_eq!(Err(TrieError::MemoryExceeded), trie.try_insert("other"));
+        assert_eq!(Ok(()), trie.try_insert("words"));
+        assert_eq!(Ok(()), trie.try_insert("wor"));
+        assert_eq!(Err(TrieError::MemoryExceeded), trie.try_insert("wordy"));
+        assert_eq!(5, trie.node_count());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_memory_limit() {
+        use crate::DataTrie;
+
+        let node_bytes = size_of::<(char, crate::trie_node::TrieDataNode<u64>)>();
+        let mut trie = DataTrie::<u64>::with_limits(TrieLimits {
+            max_memory_bytes: Some(3 * node_bytes),
+            ..Default::default()
+        });
+
+        assert_eq!(Ok(()), trie.try_insert("abc", 1));
+        assert_eq!(Ok(()), trie.try_insert("ab", 2));
+        assert_eq!(Err(TrieError::MemoryExceeded), trie.try_insert("b", 3));
+        assert_eq!(
+            Err(TrieError::MemoryExceeded),
+            trie.try_insert_no_data("abcd")
+        );
+        assert_eq!(3, trie.node_count());
+    }
+
+    #[test]
+    fn try_insert_over_limit_inserts_nothing() {
+        let mut trie = Trie::with_limits(TrieLimits {
+            max_words: Some(1),
+            ..Default::default()
+        });
+
+        assert_eq!(Ok(()), trie.try_insert("word1"));
+        assert_eq!(Err(TrieError::TooManyWords), trie.try_insert("word2"));
+        assert_eq!(vec![String::from("word1")], trie.get_all());
+        assert_eq!(5, trie.node_count());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "the word exceeds the limits of the trie")]
+    fn insert_over_limit_panics_in_debug() {
+        let mut trie = Trie::with_limits(TrieLimits {
+            max_words: Some(1),
+            ..Default::default()
+        });
+
+        trie.insert("word1");
+        trie.insert("word2");
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_try_insert_over_limit_inserts_nothing() {
+        use crate::DataTrie;
+
+        let mut trie = DataTrie::with_limits(TrieLimits {
+            max_word_len: Some(4),
+            max_data_per_word: Some(1),
+            ..Default::default()
+        });
+
+        assert_eq!(Ok(()), trie.try_insert("word", 1));
+        assert_eq!(Err(TrieError::TooManyData), trie.try_insert("word", 2));
+        assert_eq!(Err(TrieError::WordTooLong), trie.try_insert("words", 3));
+        assert_eq!(
+            Err(TrieError::WordTooLong),
+            trie.try_insert_no_data("other")
+        );
+
+        assert_eq!(vec![String::from("word")], trie.get_all());
+        assert_eq!(vec![&1], trie.get_data("word", false).unwrap());
+        assert_eq!(4, trie.node_count());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_limits() {
+        use crate::DataTrie;
+
+        let mut trie = DataTrie::with_limits(TrieLimits {
+            max_word_len: Some(5),
+            max_nodes: Some(5),
+            max_memory_bytes: None,
+            max_words: Some(2),
+            max_data_per_word: Some(2),
+        });
+
+        assert_eq!(Ok(()), trie.try_insert("word", 1));
+        assert_eq!(Ok(()), trie.try_insert("word", 2));
+        assert_eq!(Err(TrieError::TooManyData), trie.try_insert("word", 3));
+        assert_eq!(Ok(()), trie.try_insert_no_data("word"));
+        assert_eq!(Err(TrieError::WordTooLong), trie.try_insert("wordss", 3));
+        assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("ok", 3));
+        assert_eq!(Ok(()), trie.try_insert_no_data("words"));
+        assert_eq!(Err(TrieError::TooManyWords), trie.try_insert("wor", 3));
+
+        assert_eq!(vec![&1, &2], trie.get_data("", true).unwrap());
+        assert_eq!(5, trie.node_count());
+
+        assert_eq!(Some(vec![]), trie.remove("words"));
+        assert_eq!(4, trie.node_count());
+        assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("ok", 3));
+        assert_eq!(Ok(()), trie.try_insert("o", 3));
+        assert_eq!(5, trie.node_count());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie_node_count_tracking() {
+        use crate::DataTrie;
+
+        let mut trie = DataTrie::new();
+        let assert_node_count = |trie: &DataTrie<i32>| {
+            assert_eq!(trie.stats().node_count - 1, trie.node_count());
+        };
+
+        trie.insert("apple", 1);
+        trie.insert("apricot", 2);
+        trie.insert_no_data("banana");
+        trie.insert("band", 3);
+        assert_node_count(&trie);
+
+        trie.retain(|_, data| *data != 2, true);
+        assert_node_count(&trie);
+        assert_eq!(
+            Some(vec![3]),
+            trie.remove_data_where("band", |_| true, true)
+        );
+        assert_node_count(&trie);
+
+        let mut other = DataTrie::new();
+        other.insert("apples", 4);
+        other.insert("cherry", 5);
+        trie += other;
+        assert_node_count(&trie);
+
+        let mut other = DataTrie::new();
+        other.insert("cherries", 7);
+        other.insert("date", 8);
+        other.insert("fig", 9);
+        let trie = trie + other;
+        assert_node_count(&trie);
+
+        let mut trie = trie.map_data(|_, data| data * 2);
+        assert_node_count(&trie);
+        assert_eq!(Some(vec![2, 8]), trie.remove_prefix("a"));
+        assert_node_count(&trie);
+    }
+}
+
+#[cfg(feature = "data")]
+#[cfg(test)]
+mod phrase_trie_tests {
+    use crate::{PhraseTrie, StorageLayout};
+
+    #[test]
+    fn insert_and_get() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        trie.insert("new  york", 6);
+
+        assert_eq!(5, trie.len());
+        assert_eq!(vec![&1, &6], trie.get_data("new york").unwrap());
+        assert_eq!(None, trie.get_data("new"));
+        assert_eq!(None, trie.get_data("boston"));
+
+        let mut found_phrases = trie.get("new york").unwrap();
+        found_phrases.sort();
+        assert_eq!(
+            vec![String::from("new york"), String::from("new york city")],
+            found_phrases
+        );
+
+        assert_eq!(None, trie.get("new york state"));
+        assert_eq!(5, trie.get_all().len());
+    }
+
+    #[test]
+    fn tokens_are_whole() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        assert!(trie.contains("york"));
+        assert!(!trie.contains("yor"));
+        assert!(!trie.contains("new"));
+        assert_eq!(None, trie.get("ne"));
+    }
+
+    #[test]
+    fn remove() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        assert_eq!(None, trie.remove("new"));
+        assert_eq!(None, trie.remove("boston"));
+        assert_eq!(Some(vec![2]), trie.remove("new york city"));
+        assert_eq!(Some(vec![1]), trie.remove("new york"));
+        assert!(!trie.contains("new york"));
+        assert!(trie.contains("new jersey"));
+        assert_eq!(3, trie.len());
+
+        trie.insert("new york", 7);
+        assert_eq!(vec![&7], trie.get_data("new york").unwrap());
+    }
+
+    #[test]
+    fn removed_tokens_are_freed() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        assert_eq!(5, trie.token_count());
+
+        trie.remove("city hall");
+        assert_eq!(4, trie.token_count());
+        trie.remove("new york city");
+        assert_eq!(3, trie.token_count());
+
+        // Freed keys are given to new tokens, and repeated tokens are counted per use.
+        trie.insert("boston boston", 6);
+        trie.insert("boston hall", 7);
+        assert_eq!(5, trie.token_count());
+        assert!(!trie.contains("city hall"));
+        assert_eq!(vec![&6], trie.get_data("boston boston").unwrap());
+
+        trie.remove("boston boston");
+        assert_eq!(5, trie.token_count());
+        trie.remove("boston hall");
+        assert_eq!(3, trie.token_count());
+
+        let mut found_phrases = trie.get_all();
+        found_phrases.sort();
+        assert_eq!(
+            vec![
+                String::from("new jersey"),
+                String::from("new york"),
+                String::from("york")
+            ],
+            found_phrases
+        );
+    }
+
+    #[test]
+    fn with_layout() {
+        let layout = StorageLayout {
+            promotion_threshold: 1,
+            sorted_small: true,
+            dense_ascii: false,
+        };
+        let mut trie = PhraseTrie::with_layout(layout);
+
+        trie.insert("new york", 1);
+        trie.insert("new jersey", 2);
+        trie.insert("new mexico", 3);
+        trie.remove("new jersey");
+
+        assert_eq!(layout, trie.layout());
+        assert_eq!(2, trie.len());
+        assert_eq!(vec![&3], trie.get_data("new mexico").unwrap());
+    }
+
+    #[test]
+    fn insert_no_data() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        trie.insert_no_data("boston");
+        trie.insert_no_data("new york");
+
+        assert_eq!(6, trie.len());
+        assert_eq!(Vec::<&u32>::new(), trie.get_data("boston").unwrap());
+        assert_eq!(vec![&1], trie.get_data("new york").unwrap());
+    }
+
+    #[test]
+    fn find_phrases_in() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        let text = "In new york city, near city hall, york and new jersey and new ark.";
+
+        let found_phrases = trie
+            .find_phrases_in(text)
+            .iter()
+            .map(|m| (m.start(), m.end(), m.word(), m.data()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (3, 16, "new york city", &[2][..]),
+                (23, 32, "city hall", &[5][..]),
+                (34, 38, "york", &[4][..]),
+                (43, 53, "new jersey", &[3][..]),
+            ],
+            found_phrases
+        );
+    }
+
+    #[test]
+    fn find_phrases_in_falls_back_to_shorter() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        let found_phrases = trie
+            .find_phrases_in("new york state")
+            .iter()
+            .map(|m| m.word())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["new york"], found_phrases);
+        assert!(trie.find_phrases_in("").is_empty());
+        assert!(
+            PhraseTrie::<u32>::new()
+                .find_phrases_in("new york")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn custom_tokenizer() {
+        let mut trie = PhraseTrie::with_tokenizer(|text| {
+            text.char_indices()
+                .map(|(position, character)| {
+                    (position, &text[position..position + character.len_utf8()])
+                })
+                .collect()
+        });
+
+        trie.insert("ab", 1);
+        trie.insert("abc", 2);
+
+        assert_eq!(vec![(0, "a"), (1, "b")], trie.tokenize("ab"));
+        assert_eq!(
+            vec!["abc", "ab"],
+            trie.find_phrases_in("xabcab")
+                .iter()
+                .map(|m| m.word())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn clear() {
+        let mut trie = PhraseTrie::new();
+
+        trie.insert("new york", 1);
+        trie.insert("new york city", 2);
+        trie.insert("new jersey", 3);
+        trie.insert("york", 4);
+        trie.insert("city hall", 5);
+
+        trie.clear();
+
+        assert!(trie.is_empty());
+        assert_eq!(Vec::<String>::new(), trie.get_all());
+        assert!(trie.find_phrases_in("new york").is_empty());
+    }
+}
+
+#[cfg(feature = "data")]
+#[cfg(test)]
+mod index_tests {
+    use crate::Index;
+
+    #[test]
+    fn search_prefix() {
+        let mut index = Index::new();
+
+        index.add_document(1, "The cat sat on the mat.");
+        index.add_document(2, "Cats and dogs, cats and mice.");
+        index.add_document(3, "A dog on a log.");
+
+        assert_eq!(vec![2, 1], index.search_prefix("cat"));
+        assert_eq!(vec![2, 3], index.search_prefix("dog"));
+        assert_eq!(vec![1, 3], index.search_prefix("ON"));
+        assert_eq!(vec![1, 2, 3], index.search_prefix(""));
+        assert!(index.search_prefix("bird").is_empty());
+    }
+
+    #[test]
+    fn postings_and_frequencies() {
+        let mut index = Index::new();
+
+        index.add_document(1, "The cat sat on the mat.");
+        index.add_document(2, "Cats and dogs, cats and mice.");
+        index.add_document(3, "A dog on a log.");
+
+        let found_positions = index
+            .postings("the")
+            .iter()
+            .map(|posting| (posting.doc_id(), posting.position()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(1, 0), (1, 4)], found_positions);
+
+        assert_eq!(2, index.term_frequency("cats", 2));
+        assert_eq!(0, index.term_frequency("cats", 1));
+        assert_eq!(2, index.document_frequency("on"));
+        assert_eq!(0, index.document_frequency("bird"));
+        assert_eq!(2, index.collection_frequency("a"));
+        assert!(index.postings("bird").is_empty());
+    }
+
+    #[test]
+    fn remove_document() {
+        let mut index = Index::new();
+
+        index.add_document(1, "The cat sat on the mat.");
+        index.add_document(2, "Cats and dogs, cats and mice.");
+        index.add_document(3, "A dog on a log.");
+
+        assert!(index.remove_document(2));
+        assert!(!index.remove_document(2));
+        assert!(!index.contains_document(2));
+        assert_eq!(2, index.len());
+
+        assert_eq!(vec![1], index.search_prefix("cat"));
+        assert_eq!(vec![3], index.search_prefix("dog"));
+        assert!(index.search_prefix("mice").is_empty());
+        assert_eq!(0, index.collection_frequency("and"));
+    }
+
+    #[test]
+    fn replace_document() {
+        let mut index = Index::new();
+
+        index.add_document(1, "The cat sat on the mat.");
+        index.add_document(2, "Cats and dogs, cats and mice.");
+        index.add_document(3, "A dog on a log.");
+
+        index.add_document(1, "A bird on a wire.");
+
+        assert_eq!(3, index.len());
+        assert_eq!(vec![1], index.search_prefix("wi"));
+        assert!(index.postings("mat").is_empty());
+        assert_eq!(vec![(1, 2)], {
+            index
+                .postings("on")
+                .iter()
+                .filter(|posting| posting.doc_id() == 1)
+                .map(|posting| (posting.doc_id(), posting.position()))
+                .collect::<Vec<_>>()
+        });
+    }
+
+    #[test]
+    fn clear() {
+        let mut index = Index::new();
+
+        index.add_document(1, "The cat sat on the mat.");
+        index.add_document(2, "Cats and dogs, cats and mice.");
+        index.add_document(3, "A dog on a log.");
+
+        index.clear();
+
+        assert!(index.is_empty());
+        assert!(index.search_prefix("").is_empty());
+    }
+}
+
+#[cfg(test)]
+mod spell_checker_tests {
+    use crate::{CountingTrie, EditCosts, SpellChecker};
+
+    fn suggested_words(checker: &SpellChecker, word: &str, max_cost: u32) -> Vec<String> {
+        checker
+            .suggest(word, max_cost, 10)
+            .iter()
+            .map(|suggestion| suggestion.word().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn transposition() {
+        let mut checker = SpellChecker::new();
+
+        checker.insert_n("the", 100);
+        checker.insert_n("then", 20);
+        checker.insert_n("they", 30);
+        checker.insert_n("form", 10);
+        checker.insert_n("from", 50);
+        checker.insert_n("receive", 5);
+
+        let suggestions = checker.suggest("recieve", 1, 10);
+        assert_eq!(1, suggestions.len());
+        assert_eq!("receive", suggestions[0].word());
+        assert_eq!(1, suggestions[0].cost());
+
+        // "from" is a transposition away and "form" is the word itself,
+        // so the frequency of "from" doesn't matter.
+        assert_eq!(vec!["form", "from"], suggested_words(&checker, "form", 1));
+    }
+
+    #[test]
+    fn keyboard_adjacency() {
+        let mut checker = SpellChecker::new();
+
+        checker.insert_n("the", 100);
+        checker.insert_n("then", 20);
+        checker.insert_n("they", 30);
+        checker.insert_n("form", 10);
+        checker.insert_n("from", 50);
+        checker.insert_n("receive", 5);
+
+        // 'r' is next to 'e', 'p' is far from it.
+        assert_eq!(1, checker.suggest("thr", 1, 1)[0].cost());
+        assert!(checker.suggest("thp", 1, 1).is_empty());
+        assert_eq!(2, checker.suggest("thp", 2, 1)[0].cost());
+
+        // Keys in the row below: 'a' is under 'q' and 'w', 'z' is under 'a' and 's'.
+        let mut checker = SpellChecker::new();
+        checker.insert("q");
+        checker.insert("w");
+        checker.insert("e");
+        assert_eq!(vec!["q", "w"], suggested_words(&checker, "a", 1));
+
+        checker.insert("s");
+        checker.insert("d");
+        assert_eq!(vec!["s"], suggested_words(&checker, "z", 1));
+        assert_eq!(vec!["e", "s"], suggested_words(&checker, "D", 1));
+    }
+
+    #[test]
+    fn ranking_by_cost_and_frequency() {
+        let mut checker = SpellChecker::new();
+
+        checker.insert_n("the", 100);
+        checker.insert_n("then", 20);
+        checker.insert_n("they", 30);
+        checker.insert_n("form", 10);
+        checker.insert_n("from", 50);
+        checker.insert_n("receive", 5);
+
+        let found_suggestions = checker
+            .suggest("th", 2, 10)
+            .iter()
+            .map(|suggestion| {
+                (
+                    suggestion.word().to_string(),
+                    suggestion.cost(),
+                    suggestion.count(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(String::from("the"), 2, 100)], found_suggestions);
+
+        assert_eq!(
+            vec!["the", "they", "then"],
+            suggested_words(&checker, "thex", 2)
+        );
+        assert_eq!(2, checker.suggest("thex", 2, 2).len());
+    }
+
+    #[test]
+    fn insertions_and_deletions() {
+        let checker = SpellChecker::from({
+            let mut words = CountingTrie::new();
+            words.insert("abc");
+            words
+        });
+
+        assert_eq!(2, checker.suggest("ab", 2, 1)[0].cost());
+        assert_eq!(2, checker.suggest("abcd", 2, 1)[0].cost());
+        assert_eq!(6, checker.suggest("", 6, 1)[0].cost());
+        assert!(checker.suggest("a", 3, 1).is_empty());
+
+        let mut checker = SpellChecker::with_costs(EditCosts {
+            insertion: 1,
+            deletion: 3,
+            ..EditCosts::default()
+        });
+        checker.insert("abc");
+
+        assert_eq!(1, checker.suggest("ab", 3, 1)[0].cost());
+        assert_eq!(3, checker.suggest("abcd", 3, 1)[0].cost());
+    }
+
+    #[test]
+    fn correct_words() {
+        let mut checker = SpellChecker::new();
+
+        checker.insert_n("the", 100);
+        checker.insert_n("then", 20);
+        checker.insert_n("they", 30);
+        checker.insert_n("form", 10);
+        checker.insert_n("from", 50);
+        checker.insert_n("receive", 5);
+
+        assert!(checker.is_correct("they"));
+        assert!(!checker.is_correct("thy"));
+        assert_eq!(0, checker.suggest("they", 0, 1)[0].cost());
+        assert!(checker.suggest("thy", 0, 1).is_empty());
+        assert_eq!(6, checker.len());
+        assert!(SpellChecker::new().suggest("the", 10, 10).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod anagram_tests {
+    use crate::Trie;
+
+    #[test]
+    fn anagrams() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "a", "at", "ate", "eat", "tea", "teat", "east", "seat", "sate", "tease", "eats",
+            "stain", "satin", "retains", "nastier", "zoo",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(vec!["ate", "eat", "tea"], trie.anagrams("tae"));
+        assert_eq!(vec!["nastier", "retains"], trie.anagrams("aeinrst"));
+        assert_eq!(vec!["east", "eats", "sate", "seat"], trie.anagrams("seta"));
+        assert!(trie.anagrams("teaa").is_empty());
+        assert!(trie.anagrams("zo").is_empty());
+        assert!(trie.anagrams("").is_empty());
+    }
+
+    #[test]
+    fn repeated_letters() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "a", "at", "ate", "eat", "tea", "teat", "east", "seat", "sate", "tease", "eats",
+            "stain", "satin", "retains", "nastier", "zoo",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(vec!["teat"], trie.anagrams("ttea"));
+        assert_eq!(vec!["zoo"], trie.anagrams("ooz"));
+        assert!(!trie.sub_anagrams("tea", 0).contains(&String::from("teat")));
+        assert!(trie.sub_anagrams("teat", 0).contains(&String::from("teat")));
+    }
+
+    #[test]
+    fn sub_anagrams() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "a", "at", "ate", "eat", "tea", "teat", "east", "seat", "sate", "tease", "eats",
+            "stain", "satin", "retains", "nastier", "zoo",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(
+            vec!["a", "at", "ate", "eat", "tea"],
+            trie.sub_anagrams("tae", 0)
+        );
+        assert_eq!(
+            vec![
+                "a", "at", "ate", "east", "eat", "eats", "nastier", "retains", "sate", "satin",
+                "seat", "stain", "tea"
+            ],
+            trie.sub_anagrams("aeinrst", 0)
+        );
+        assert!(trie.sub_anagrams("xyz", 0).is_empty());
+    }
+
+    #[test]
+    fn wildcards() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "a", "at", "ate", "eat", "tea", "teat", "east", "seat", "sate", "tease", "eats",
+            "stain", "satin", "retains", "nastier", "zoo",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(vec!["a", "zoo"], trie.sub_anagrams("zo", 1));
+        assert_eq!(vec!["a", "at"], trie.sub_anagrams("", 2));
+        assert!(trie.sub_anagrams("", 0).is_empty());
+        assert_eq!(
+            vec![
+                "a", "at", "ate", "east", "eat", "eats", "sate", "seat", "tea", "teat"
+            ],
+            trie.sub_anagrams("tea", 1)
+        );
+        assert_eq!(
+            vec![
+                "east", "eats", "sate", "satin", "seat", "stain", "tease", "teat"
+            ],
+            trie.sub_anagrams("stne", 2)
+                .into_iter()
+                .filter(|word| word.len() > 3)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn empty_word() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "a", "at", "ate", "eat", "tea", "teat", "east", "seat", "sate", "tease", "eats",
+            "stain", "satin", "retains", "nastier", "zoo",
+        ] {
+            trie.insert(word);
+        }
+
+        trie.insert("");
+
+        assert_eq!(vec![String::new()], trie.anagrams(""));
+        assert_eq!(vec!["", "a"], trie.sub_anagrams("a", 0));
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use crate::{Adjacency, Trie};
+
+    fn to_grid(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|row| row.chars().collect()).collect()
+    }
+
+    fn found_words(trie: &Trie, grid: &[Vec<char>], adjacency: Adjacency) -> Vec<String> {
+        trie.find_in_grid(grid, adjacency)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    #[test]
+    fn eight_neighbours() {
+        let mut trie = Trie::new();
+        for word in ["oath", "pea", "eat", "rain", "hike", "tea", "oat"] {
+            trie.insert(word);
+        }
+
+        let grid = to_grid(&["oaan", "etae", "ihkr", "iflv"]);
+
+        assert_eq!(
+            vec!["eat", "oat", "oath", "tea"],
+            found_words(&trie, &grid, Adjacency::Eight)
+        );
+        assert_eq!(
+            vec!["eat", "oat", "oath"],
+            found_words(&trie, &grid, Adjacency::Four)
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let mut trie = Trie::new();
+        trie.insert("oath");
+
+        let grid = to_grid(&["oaan", "etae", "ihkr", "iflv"]);
+
+        assert_eq!(
+            vec![(String::from("oath"), vec![(0, 0), (0, 1), (1, 1), (2, 1)])],
+            trie.find_in_grid(&grid, Adjacency::Four)
+        );
+    }
+
+    #[test]
+    fn cells_are_used_once() {
+        let mut trie = Trie::new();
+        trie.insert("aba");
+        trie.insert("abab");
+        trie.insert("ab");
+
+        let grid = to_grid(&["ab"]);
+        assert_eq!(vec!["ab"], found_words(&trie, &grid, Adjacency::Eight));
+
+        let grid = to_grid(&["ab", "ba"]);
+        assert_eq!(
+            vec!["ab", "aba", "abab"],
+            found_words(&trie, &grid, Adjacency::Four)
+        );
+    }
+
+    #[test]
+    fn uneven_and_empty_grids() {
+        let mut trie = Trie::new();
+        trie.insert("abc");
+        trie.insert("");
+
+        assert_eq!(
+            vec![(String::from("abc"), vec![(0, 0), (1, 0), (2, 0)])],
+            trie.find_in_grid(&to_grid(&["ax", "b", "cyz"]), Adjacency::Four)
+        );
+        assert!(trie.find_in_grid(&[], Adjacency::Eight).is_empty());
+        assert!(
+            trie.find_in_grid(&to_grid(&["", ""]), Adjacency::Eight)
+                .is_empty()
+        );
+    }
+}
+
+#[cfg(feature = "regex")]
+#[cfg(test)]
+mod regex_tests {
+    use crate::Trie;
+
+    fn found_words(trie: &Trie, pattern: &str) -> Vec<String> {
+        let mut found_words = trie.find_regex(pattern).unwrap();
+        found_words.sort();
+        found_words
+    }
+
+    #[test]
+    fn whole_words() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "able",
+            "unable",
+            "unreadable",
+            "readable",
+            "reusable",
+            "usable",
+            "unusable",
+            "rethink",
+            "undo",
+            "",
+            "ünable",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(
+            vec!["readable", "reusable", "unreadable", "unusable"],
+            found_words(&trie, "^(un|re)[a-z]+able$")
+        );
+        // Without anchors, the pattern still has to match the whole word.
+        assert_eq!(vec!["undo"], found_words(&trie, "un[a-z]o"));
+        assert!(found_words(&trie, "read").is_empty());
+        assert_eq!(
+            vec!["readable", "unreadable"],
+            found_words(&trie, ".*read.*")
+        );
+    }
+
+    #[test]
+    fn alternation_keeps_longer_matches() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "able",
+            "unable",
+            "unreadable",
+            "readable",
+            "reusable",
+            "usable",
+            "unusable",
+            "rethink",
+            "undo",
+            "",
+            "ünable",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(vec!["undo"], found_words(&trie, "un|undo"));
+        assert_eq!(
+            vec!["unable", "unusable"],
+            found_words(&trie, "un(usable|able|a)")
+        );
+    }
+
+    #[test]
+    fn empty_and_unicode_words() {
+        let mut trie = Trie::new();
+
+        for word in [
+            "able",
+            "unable",
+            "unreadable",
+            "readable",
+            "reusable",
+            "usable",
+            "unusable",
+            "rethink",
+            "undo",
+            "",
+            "ünable",
+        ] {
+            trie.insert(word);
+        }
+
+        assert_eq!(vec![""], found_words(&trie, ""));
+        assert_eq!(vec!["", "able"], found_words(&trie, "(able)?"));
+        assert_eq!(vec!["ünable"], found_words(&trie, "ü[a-z]+"));
+        assert_eq!(trie.len(), found_words(&trie, "(?s).*").len());
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let mut trie = Trie::new();
+        trie.insert("unable");
+
+        assert!(trie.find_regex("(un").is_err());
+        assert!(Trie::new().find_regex("[a-").is_err());
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn data_trie() {
+        let mut data_trie = crate::DataTrie::new();
+        data_trie.insert("unable", 1);
+        data_trie.insert("unable", 2);
+        data_trie.insert_no_data("undo");
+        data_trie.insert("redo", 3);
+
+        let mut found_words = data_trie.find_regex("(un|re)do|.*able").unwrap();
+        found_words.sort();
+
+        assert_eq!(
+            vec![
+                (String::from("redo"), &[3][..]),
+                (String::from("unable"), &[1, 2][..]),
+                (String::from("undo"), &[][..]),
+            ],
+            found_words
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg(test)]
+mod serde_tests {
+    use crate::{CountingTrie, Trie};
+
+    /// Words whose nodes keep their children in every kind of storage:
+    /// vectors, a dense ASCII array and a hash map with non-ASCII characters.
+    const WORDS: [&str; 12] = [
+        "a", "ab", "abc", "b", "c", "d", "e", "f", "ča", "ćb", "đc", "žd",
+    ];
+
+    #[test]
+    fn trie_round_trip() {
+        let mut trie = Trie::new();
+        for word in WORDS {
+            trie.insert(word);
+        }
+
+        let json = serde_json::to_string(&trie).unwrap();
+        let deserialized = serde_json::from_str::<Trie>(&json).unwrap();
+
+        assert_eq!(trie, deserialized);
+        assert!(deserialized.contains("žd"));
+    }
+
+    #[test]
+    fn trie_keeps_its_layout() {
+        use crate::StorageLayout;
+
+        let layout = StorageLayout {
+            promotion_threshold: 2,
+            sorted_small: true,
+            dense_ascii: false,
+        };
+        let mut trie = Trie::with_layout(layout);
+        for word in WORDS {
+            trie.insert(word);
+        }
+
+        let json = serde_json::to_string(&trie).unwrap();
+        let deserialized = serde_json::from_str::<Trie>(&json).unwrap();
+
+        let (stats, deserialized_stats) = (trie.stats(), deserialized.stats());
+        assert_eq!(layout, deserialized.layout());
+        assert_eq!(1, deserialized_stats.large_storage_nodes);
+        assert_eq!(
+            stats.sorted_small_storage_nodes,
+            deserialized_stats.sorted_small_storage_nodes
+        );
+        assert_eq!(0, deserialized_stats.small_storage_nodes);
+        assert_eq!(0, deserialized_stats.dense_ascii_storage_nodes);
+    }
+
+    #[test]
+    fn counting_trie_round_trip() {
+        let mut trie = CountingTrie::new();
+        for (n, word) in WORDS.into_iter().enumerate() {
+            trie.insert_n(word, n + 1);
+        }
+
+        let json = serde_json::to_string(&trie).unwrap();
+        let mut deserialized = serde_json::from_str::<CountingTrie>(&json).unwrap();
+
+        assert_eq!(trie, deserialized);
+        assert_eq!(3, deserialized.count("abc"));
+
+        deserialized.insert("abcd");
+        assert_eq!(1, deserialized.count_prefix("abc"));
+        assert_eq!(4, deserialized.count_prefix("ab"));
+    }
+
+    #[cfg(feature = "data")]
+    #[test]
+    fn trie_map_round_trip() {
+        use crate::TrieMap;
+
+        let trie_map = WORDS
+            .into_iter()
+            .enumerate()
+            .map(|(n, word)| (word, n))
+            .collect::<TrieMap<_>>();
+
+        let json = serde_json::to_string(&trie_map).unwrap();
+        let deserialized = serde_json::from_str::<TrieMap<usize>>(&json).unwrap();
+
+        assert_eq!(trie_map, deserialized);
+        assert_eq!(Some(&11), deserialized.get("žd"));
+    }
 }
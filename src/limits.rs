@@ -0,0 +1,106 @@
+use crate::TrieError;
+use crate::trie::get_characters;
+use crate::trie_node::TrieNode;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+/// Limits on the growth of a trie, enforced when inserting words.
+/// Tries that store user-submitted words can use them to bound the memory
+/// a single word, or all of them together, may take. Every limit is
+/// disabled by default.
+///
+/// Limits aren't enforced when merging tries, and lowering them
+/// doesn't remove anything that is already in the trie.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TrieLimits {
+    /// Maximum number of characters in a word.
+    pub max_word_len: Option<usize>,
+    /// Maximum number of nodes in the trie, not counting the root.
+    pub max_nodes: Option<usize>,
+    /// Maximum number of bytes the nodes of the trie may take, not counting the root.
+    /// Every node is counted as the size of a node along with its character,
+    /// so the heap memory of child containers and data is left out.
+    pub max_memory_bytes: Option<usize>,
+    /// Maximum number of words in the trie.
+    pub max_words: Option<usize>,
+    /// Maximum number of data items attached to a single word.
+    /// Only data tries have data to limit.
+    pub max_data_per_word: Option<usize>,
+}
+
+/// What inserting a word would add to a trie: the number of characters
+/// of the word, the size of a single node, and the number of nodes, words
+/// and data items of the word before the insertion along with the number of them it adds.
+pub(crate) struct Insertion {
+    pub(crate) word_len: usize,
+    pub(crate) node_bytes: usize,
+    pub(crate) node_count: usize,
+    pub(crate) new_nodes: usize,
+    pub(crate) word_count: usize,
+    pub(crate) new_words: usize,
+    pub(crate) data_count: usize,
+    pub(crate) new_data: usize,
+}
+
+impl TrieLimits {
+    /// Function returns true if no limit is set.
+    pub(crate) fn is_unlimited(&self) -> bool {
+        *self == TrieLimits::default()
+    }
+
+    /// Function returns the error for the first limit that the insertion exceeds.
+    /// Only the word itself and what the insertion adds are checked, so words
+    /// that are already in the trie can be inserted again after lowering the limits.
+    pub(crate) fn check(&self, insertion: Insertion) -> Result<(), TrieError> {
+        let exceeds = |limit: Option<usize>, count: usize, added: usize| {
+            added > 0 && limit.is_some_and(|max| count + added > max)
+        };
+
+        if exceeds(self.max_word_len, 0, insertion.word_len) {
+            Err(TrieError::WordTooLong)
+        } else if exceeds(self.max_nodes, insertion.node_count, insertion.new_nodes) {
+            Err(TrieError::CapacityExceeded)
+        } else if exceeds(
+            self.max_memory_bytes,
+            insertion.node_count * insertion.node_bytes,
+            insertion.new_nodes * insertion.node_bytes,
+        ) {
+            Err(TrieError::MemoryExceeded)
+        } else if exceeds(self.max_words, insertion.word_count, insertion.new_words) {
+            Err(TrieError::TooManyWords)
+        } else if exceeds(
+            self.max_data_per_word,
+            insertion.data_count,
+            insertion.new_data,
+        ) {
+            Err(TrieError::TooManyData)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Function walks the path of a word from 'root' and returns the number of
+/// characters of the word, the number of nodes inserting it would add,
+/// and the last node of the word if all of its nodes are already in the trie.
+pub(crate) fn insertion_path<'a, N: TrieNode>(
+    root: &'a N,
+    word: &str,
+) -> (usize, usize, Option<&'a N>) {
+    let mut current = Some(root);
+    let mut word_len = 0;
+    let mut new_nodes = 0;
+
+    for character in get_characters(word) {
+        current = current.and_then(|node| node.children().get(character));
+        word_len += 1;
+        new_nodes += current.is_none() as usize;
+    }
+
+    (word_len, new_nodes, current)
+}
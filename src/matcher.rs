@@ -0,0 +1,302 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use thin_vec::ThinVec;
+
+use crate::child_storage::{ChildStorage, StorageLayout};
+
+/// Index of the root state; it is also the failure target of every depth-one state.
+const ROOT: u32 = 0;
+
+/// Singular automaton state. Transitions are the trie edges of the node the
+/// state was built from, kept in the same child container as the trie.
+struct State<D> {
+    transitions: ChildStorage<u32>,
+    fail: u32,
+    /// Nearest state on the failure chain that is an end of a word.
+    output: Option<u32>,
+    /// Length of the word spelled by the path to this state, in bytes.
+    byte_len: usize,
+    is_word: bool,
+    data: ThinVec<D>,
+}
+
+impl<D> State<D> {
+    fn new(byte_len: usize) -> Self {
+        State {
+            transitions: ChildStorage::default(),
+            fail: ROOT,
+            output: None,
+            byte_len,
+            is_word: false,
+            data: ThinVec::new(),
+        }
+    }
+}
+
+/// A single occurrence of a word found in the scanned text.
+/// Positions are byte offsets into the scanned text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Match<'a, D> {
+    start: usize,
+    end: usize,
+    word: &'a str,
+    data: &'a [D],
+}
+
+impl<'a, D> Match<'a, D> {
+    /// Function returns a match of 'word', found between 'start' and 'end' in the text.
+    pub(crate) fn new(start: usize, end: usize, word: &'a str, data: &'a [D]) -> Self {
+        Match {
+            start,
+            end,
+            word,
+            data,
+        }
+    }
+
+    /// Returns the byte offset where the match begins.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the byte offset right after the end of the match.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the matched word.
+    pub fn word(&self) -> &'a str {
+        self.word
+    }
+
+    /// Returns the data associated with the matched word.
+    /// Always empty for matchers built from a regular trie.
+    pub fn data(&self) -> &'a [D] {
+        self.data
+    }
+}
+
+/// Aho-Corasick automaton built from a trie, used for finding every
+/// occurrence of every word of the trie inside some text in one scan.
+///
+/// The text is scanned character by character, without normalization,
+/// so the words are matched exactly as they are stored in the trie.
+/// The empty word is never matched.
+pub struct Matcher<D = ()> {
+    states: Vec<State<D>>,
+}
+
+impl<D> Matcher<D> {
+    /// Builds the automaton by consuming the trie nodes in breadth-first order.
+    /// Every node becomes one state, with its data moved into the state.
+    /// 'into_parts' splits a node into its children and its association.
+    pub(crate) fn from_root<N>(
+        root: N,
+        into_parts: impl Fn(N) -> (ChildStorage<N>, Option<ThinVec<D>>),
+    ) -> Self {
+        let mut states = vec![State::new(0)];
+        let mut queue = VecDeque::from([(root, ROOT)]);
+
+        while let Some((node, index)) = queue.pop_front() {
+            let (children, association) = into_parts(node);
+
+            if let Some(data_vec) = association {
+                let state = &mut states[index as usize];
+                // The empty word can't be found in text, so the root is never an output.
+                state.is_word = index != ROOT;
+                state.data = data_vec;
+            }
+
+            for (character, child) in children {
+                let child_index = states.len() as u32;
+                let byte_len = states[index as usize].byte_len + character.len_utf8();
+
+                states.push(State::new(byte_len));
+                states[index as usize].transitions.insert_direct(
+                    character,
+                    child_index,
+                    StorageLayout::default(),
+                );
+                queue.push_back((child, child_index));
+            }
+        }
+
+        let mut matcher = Matcher { states };
+        matcher.link();
+        matcher
+    }
+
+    /// Computes failure and output links. States are in breadth-first order,
+    /// so the failure target of a state is always computed before the state itself.
+    fn link(&mut self) {
+        for index in 0..self.states.len() {
+            let transitions = self.states[index]
+                .transitions
+                .iter()
+                .map(|(&character, &child)| (character, child))
+                .collect::<Vec<_>>();
+
+            for (character, child) in transitions {
+                let fail = if index as u32 == ROOT {
+                    ROOT
+                } else {
+                    self.next_state(self.states[index].fail, character)
+                };
+
+                self.states[child as usize].fail = fail;
+            }
+        }
+
+        for index in 1..self.states.len() {
+            let fail = &self.states[self.states[index].fail as usize];
+            let output = if fail.is_word {
+                Some(self.states[index].fail)
+            } else {
+                fail.output
+            };
+
+            self.states[index].output = output;
+        }
+    }
+
+    /// Follows failure links from 'state' until a transition over 'character'
+    /// is found, falling back to the root.
+    fn next_state(&self, mut state: u32, character: char) -> u32 {
+        loop {
+            if let Some(&next) = self.states[state as usize].transitions.get(character) {
+                return next;
+            }
+
+            if state == ROOT {
+                return ROOT;
+            }
+
+            state = self.states[state as usize].fail;
+        }
+    }
+
+    /// Returns every occurrence of every word in 'text', including
+    /// overlapping ones. Matches are ordered by their end position,
+    /// and matches ending at the same position from the longest to the shortest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("he");
+    /// trie.insert("she");
+    /// trie.insert("hers");
+    ///
+    /// let matcher = trie.into_matcher();
+    /// let found_words = matcher
+    ///     .find_overlapping("ushers")
+    ///     .iter()
+    ///     .map(|m| (m.start(), m.end(), m.word()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec![(1, 4, "she"), (2, 4, "he"), (2, 6, "hers")], found_words);
+    /// ```
+    pub fn find_overlapping<'a>(&'a self, text: &'a str) -> Vec<Match<'a, D>> {
+        let mut found_matches = Vec::new();
+        let mut state = ROOT;
+
+        for (position, character) in text.char_indices() {
+            state = self.next_state(state, character);
+            let end = position + character.len_utf8();
+
+            let mut output = if self.states[state as usize].is_word {
+                Some(state)
+            } else {
+                self.states[state as usize].output
+            };
+
+            while let Some(found) = output {
+                let found_state = &self.states[found as usize];
+                let start = end - found_state.byte_len;
+
+                found_matches.push(Match::new(start, end, &text[start..end], &found_state.data));
+
+                output = found_state.output;
+            }
+        }
+
+        found_matches
+    }
+
+    /// Returns non-overlapping occurrences of words in 'text'. The scan
+    /// continues after the end of each match, and out of all matches starting
+    /// at the leftmost position, the longest one is picked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut data_trie = DataTrie::new();
+    ///
+    /// data_trie.insert("new", 1);
+    /// data_trie.insert("new york", 2);
+    /// data_trie.insert("york", 3);
+    ///
+    /// let matcher = data_trie.into_matcher();
+    /// let found_data = matcher
+    ///     .find_leftmost_longest("new york and york")
+    ///     .iter()
+    ///     .map(|m| (m.word(), m.data()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec![("new york", &[2][..]), ("york", &[3][..])], found_data);
+    /// ```
+    pub fn find_leftmost_longest<'a>(&'a self, text: &'a str) -> Vec<Match<'a, D>> {
+        let mut found_matches = Vec::new();
+        let mut resume = 0;
+
+        while let Some(found) = self.leftmost_longest_from(text, resume) {
+            resume = found.end;
+            found_matches.push(found);
+        }
+
+        found_matches
+    }
+
+    /// Scans 'text' from the byte offset 'from' and returns the leftmost-longest match.
+    /// The longest word ending at each position is kept as the candidate while it
+    /// starts at or before the current one. The scan stops once the path of the
+    /// current state starts after the candidate, because no later match can start
+    /// at or before it anymore.
+    fn leftmost_longest_from<'a>(&'a self, text: &'a str, from: usize) -> Option<Match<'a, D>> {
+        let mut candidate: Option<Match<'a, D>> = None;
+        let mut state = ROOT;
+
+        for (offset, character) in text[from..].char_indices() {
+            state = self.next_state(state, character);
+            let end = from + offset + character.len_utf8();
+            let current = &self.states[state as usize];
+
+            if let Some(found) = &candidate
+                && end - current.byte_len > found.start
+            {
+                return candidate;
+            }
+
+            let longest = if current.is_word {
+                Some(state)
+            } else {
+                current.output
+            };
+
+            if let Some(longest) = longest {
+                let found_state = &self.states[longest as usize];
+                let start = end - found_state.byte_len;
+
+                if candidate.as_ref().is_none_or(|found| start <= found.start) {
+                    candidate = Some(Match::new(start, end, &text[start..end], &found_state.data));
+                }
+            }
+        }
+
+        candidate
+    }
+}
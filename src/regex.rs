@@ -0,0 +1,98 @@
+use crate::trie_node::{TrieNode, walk};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use regex_automata::dfa::dense::{self, BuildError, DFA};
+use regex_automata::dfa::{Automaton, StartKind};
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start;
+use regex_automata::{Anchored, MatchKind};
+
+/// Regular expression compiled into a DFA that is fed the bytes of a word
+/// one character at a time, and matched against the whole word.
+struct WordRegex {
+    dfa: DFA<Vec<u32>>,
+    start: StateID,
+}
+
+impl WordRegex {
+    /// Function compiles 'pattern' into a DFA whose matches start at the beginning of the word.
+    /// Every match is kept instead of only the leftmost one, so that a match
+    /// ending at the end of the word is never given up for a shorter one.
+    fn new(pattern: &str) -> Result<Self, Box<BuildError>> {
+        let dfa = dense::Builder::new()
+            .configure(
+                dense::Config::new()
+                    .match_kind(MatchKind::All)
+                    .start_kind(StartKind::Anchored),
+            )
+            .build(pattern)
+            .map_err(Box::new)?;
+
+        // Anchored start states are always built with the configuration above,
+        // and there is no text before the word to look behind at.
+        let start = dfa
+            .start_state(&start::Config::new().anchored(Anchored::Yes))
+            .unwrap();
+
+        Ok(WordRegex { dfa, start })
+    }
+
+    /// Function returns the state after reading 'character' in 'state'.
+    fn next(&self, mut state: StateID, character: char) -> StateID {
+        for &byte in character.encode_utf8(&mut [0; 4]).as_bytes() {
+            state = self.dfa.next_state(state, byte);
+        }
+
+        state
+    }
+
+    /// Function returns true if no word continuing from 'state' can match.
+    fn is_dead(&self, state: StateID) -> bool {
+        self.dfa.is_dead_state(state) || self.dfa.is_quit_state(state)
+    }
+
+    /// Function returns true if the word read up to 'state' matches. The DFA reports
+    /// matches one byte late, so the end of the word is read first.
+    fn is_match(&self, state: StateID) -> bool {
+        self.dfa.is_match_state(self.dfa.next_eoi_state(state))
+    }
+}
+
+/// Function calls 'found' with every word of the trie with the given root that
+/// matches 'pattern' as a whole, along with the last node of the word.
+///
+/// The DFA is run in lockstep with the walk, every node keeping the state its word
+/// leads to, so the subtree of a node whose state is dead isn't visited.
+pub(crate) fn find_matching<'a, N: TrieNode>(
+    root: &'a N,
+    pattern: &str,
+    mut found: impl FnMut(&'a N, &str),
+) -> Result<(), Box<BuildError>> {
+    let regex = WordRegex::new(pattern)?;
+
+    walk(
+        root,
+        &mut String::new(),
+        regex.start,
+        N::children,
+        |node, character, word, parent_state| {
+            let state = match character {
+                None => parent_state,
+                Some(character) => regex.next(parent_state, character),
+            };
+
+            if regex.is_dead(state) {
+                return None;
+            }
+
+            if node.is_associated() && regex.is_match(state) {
+                found(node, word);
+            }
+
+            Some(state)
+        },
+    );
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+
+use crate::trie_node::{TrieNode, sorted_children};
+
+/// Function returns the Graphviz DOT description of the trie with the given root.
+/// Children are ordered by their character, nodes that words end on are drawn
+/// as double circles and 'label' gives the optional text shown in a node.
+pub(crate) fn to_dot<N: TrieNode>(root: &N, label: impl Fn(&N) -> Option<String>) -> String {
+    let mut dot = String::from("digraph trie {\n    node [shape=circle, label=\"\"];\n");
+    let mut next_id = 1;
+    let mut stack = vec![(root, 0)];
+
+    while let Some((node, id)) = stack.pop() {
+        let shape = if node.is_associated() {
+            "doublecircle"
+        } else {
+            "circle"
+        };
+        let text = label(node).map_or_else(String::new, |text| escape(&text));
+        writeln!(dot, "    n{id} [shape={shape}, label=\"{text}\"];").unwrap();
+
+        let children = sorted_children(node);
+
+        for &(character, _) in &children {
+            let character = escape(character.encode_utf8(&mut [0; 4]));
+            writeln!(dot, "    n{id} -> n{next_id} [label=\"{character}\"];").unwrap();
+            next_id += 1;
+        }
+
+        // Children are pushed in reverse, so that they are described in order.
+        let first_child_id = next_id - children.len();
+        stack.extend(
+            children
+                .into_iter()
+                .enumerate()
+                .rev()
+                .map(|(position, (_, child))| (child, first_child_id + position)),
+        );
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Function escapes the text for a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|character| match character {
+            '"' | '\\' => format!("\\{character}"),
+            c if c.is_control() => c.escape_debug().to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Indented drawing of a trie, one node per line, for debugging.
+/// Children are ordered by their character and word ends are marked with '*'.
+/// Nodes deeper than 'max_depth' characters aren't drawn and drawing stops
+/// after 'max_nodes' nodes below the root; every skipped part is marked with '...'.
+pub(crate) struct TreeDisplay<'a, N> {
+    root: &'a N,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+}
+
+impl<'a, N: TrieNode> TreeDisplay<'a, N> {
+    /// Returns a new drawing of the trie with the given root.
+    pub(crate) fn new(root: &'a N, max_depth: Option<usize>, max_nodes: Option<usize>) -> Self {
+        TreeDisplay {
+            root,
+            max_depth,
+            max_nodes,
+        }
+    }
+}
+
+impl<N: TrieNode> TreeDisplay<'_, N> {
+    /// Function returns the entries of the children of a node, in reverse
+    /// so that the first child is popped from the stack first.
+    fn child_entries<'b>(node: &'b N, depth: usize, indentation: &str) -> Vec<Entry<'b, N>> {
+        let children = sorted_children(node);
+        let last_position = children.len().saturating_sub(1);
+
+        children
+            .into_iter()
+            .enumerate()
+            .rev()
+            .map(|(position, (character, child))| Entry {
+                node: child,
+                character,
+                depth,
+                indentation: indentation.to_string(),
+                is_last: position == last_position,
+            })
+            .collect()
+    }
+}
+
+/// Node waiting to be drawn, with the lines drawn before it
+/// and whether it's the last child of its parent.
+struct Entry<'a, N> {
+    node: &'a N,
+    character: char,
+    depth: usize,
+    indentation: String,
+    is_last: bool,
+}
+
+impl<N: TrieNode> fmt::Display for TreeDisplay<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let word_end = |node: &N| if node.is_associated() { " *" } else { "" };
+        let is_too_deep = |depth: usize| self.max_depth.is_some_and(|max_depth| depth > max_depth);
+
+        writeln!(f, "(root){}", word_end(self.root))?;
+
+        if self.root.children().is_empty() {
+            return Ok(());
+        }
+
+        if is_too_deep(1) {
+            return writeln!(f, "└── ...");
+        }
+
+        let mut stack = Self::child_entries(self.root, 1, "");
+        let mut drawn_nodes = 0;
+
+        while let Some(entry) = stack.pop() {
+            let indentation = entry.indentation;
+
+            if self
+                .max_nodes
+                .is_some_and(|max_nodes| drawn_nodes >= max_nodes)
+            {
+                return writeln!(f, "{indentation}└── ...");
+            }
+
+            let (branch, continuation) = if entry.is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            writeln!(
+                f,
+                "{indentation}{branch}{}{}",
+                entry.character,
+                word_end(entry.node)
+            )?;
+            drawn_nodes += 1;
+
+            let child_indentation = format!("{indentation}{continuation}");
+
+            if entry.node.children().is_empty() {
+                continue;
+            }
+
+            if is_too_deep(entry.depth + 1) {
+                writeln!(f, "{child_indentation}└── ...")?;
+                continue;
+            }
+
+            stack.extend(Self::child_entries(
+                entry.node,
+                entry.depth + 1,
+                &child_indentation,
+            ));
+        }
+
+        Ok(())
+    }
+}
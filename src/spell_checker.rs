@@ -0,0 +1,379 @@
+use crate::CountingTrie;
+use crate::trie::get_characters;
+use crate::trie_node::TrieCountingNode;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Rows of a QWERTY keyboard, every row shifted a bit to the right of the one above it.
+const QWERTY_ROWS: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// Costs of the edits that turn a misspelled word into a suggestion.
+/// Edits that are common typing mistakes should cost less than the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditCosts {
+    /// Cost of a character missing from the misspelled word.
+    pub insertion: u32,
+    /// Cost of an extra character in the misspelled word.
+    pub deletion: u32,
+    /// Cost of a character typed instead of another one.
+    pub substitution: u32,
+    /// Cost of a letter typed instead of a letter next to it on a QWERTY keyboard,
+    /// regardless of the case of the letters.
+    pub adjacent_substitution: u32,
+    /// Cost of two neighbouring characters typed in the wrong order.
+    pub transposition: u32,
+}
+
+impl Default for EditCosts {
+    /// Every edit costs 2, except for adjacent substitutions and transpositions, which cost 1.
+    fn default() -> Self {
+        EditCosts {
+            insertion: 2,
+            deletion: 2,
+            substitution: 2,
+            adjacent_substitution: 1,
+            transposition: 1,
+        }
+    }
+}
+
+/// A word suggested for a misspelled word.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Suggestion {
+    word: String,
+    cost: u32,
+    count: usize,
+}
+
+impl Suggestion {
+    /// Returns the suggested word.
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// Returns the total cost of the edits that turn the misspelled word into this one.
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    /// Returns the number of times the suggested word was added to the spell checker.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Spell checker that suggests dictionary words for misspelled ones.
+///
+/// The dictionary is a counting trie, so every word knows how often it was added.
+/// Suggestions are the words that can be reached from the misspelled word by edits
+/// of a limited total cost, where the distance is the weighted Damerau-Levenshtein
+/// distance in which no character is edited twice. The distance is computed one trie
+/// node at a time, sharing the work between words with the same prefix, and
+/// subtrees that can't get under the cost limit aren't visited.
+#[derive(Debug, Default)]
+pub struct SpellChecker {
+    words: CountingTrie,
+    costs: EditCosts,
+}
+
+impl SpellChecker {
+    /// Returns a new instance of the spell checker, with the default edit costs.
+    pub fn new() -> Self {
+        Self::with_costs(EditCosts::default())
+    }
+
+    /// Returns a new instance of the spell checker, with the given edit costs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{EditCosts, SpellChecker};
+    /// let mut checker = SpellChecker::with_costs(EditCosts {
+    ///     deletion: 1,
+    ///     ..EditCosts::default()
+    /// });
+    ///
+    /// checker.insert("cat");
+    /// assert_eq!(1, checker.suggest("cart", 1, 1)[0].cost());
+    /// ```
+    pub fn with_costs(costs: EditCosts) -> Self {
+        SpellChecker {
+            words: CountingTrie::new(),
+            costs,
+        }
+    }
+
+    /// Returns the edit costs of the spell checker.
+    pub fn costs(&self) -> EditCosts {
+        self.costs
+    }
+
+    /// Returns the dictionary of the spell checker.
+    pub fn words(&self) -> &CountingTrie {
+        &self.words
+    }
+
+    /// Adds one occurrence of a word to the dictionary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SpellChecker;
+    /// let mut checker = SpellChecker::new();
+    ///
+    /// checker.insert("word");
+    /// assert!(checker.is_correct("word"));
+    /// ```
+    pub fn insert(&mut self, word: &str) {
+        self.words.insert(word);
+    }
+
+    /// Adds 'n' occurrences of a word to the dictionary, such as its count in some corpus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SpellChecker;
+    /// let mut checker = SpellChecker::new();
+    ///
+    /// checker.insert_n("word", 10);
+    /// assert_eq!(10, checker.suggest("wrod", 1, 1)[0].count());
+    /// ```
+    pub fn insert_n(&mut self, word: &str, n: usize) {
+        self.words.insert_n(word, n);
+    }
+
+    /// Returns true if the dictionary contains the word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SpellChecker;
+    /// let mut checker = SpellChecker::new();
+    ///
+    /// checker.insert("word");
+    /// assert!(checker.is_correct("word"));
+    /// assert!(!checker.is_correct("wrod"));
+    /// ```
+    pub fn is_correct(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// Returns at most 'limit' suggestions for a misspelled word, out of the dictionary words
+    /// at most 'max_cost' away from it. Suggestions are ranked by their cost, then by
+    /// the number of times they were added, and then lexicographically.
+    /// A word that is in the dictionary is suggested for itself, with a cost of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SpellChecker;
+    /// let mut checker = SpellChecker::new();
+    ///
+    /// checker.insert_n("the", 100);
+    /// checker.insert_n("then", 20);
+    /// checker.insert_n("tie", 5);
+    /// checker.insert("hte");
+    ///
+    /// let found_suggestions = checker
+    ///     .suggest("thw", 3, 3)
+    ///     .iter()
+    ///     .map(|suggestion| (suggestion.word().to_string(), suggestion.cost()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // 'w' is next to 'e' on the keyboard, so "the" is the cheapest.
+    /// assert_eq!(
+    ///     vec![(String::from("the"), 1), (String::from("hte"), 2), (String::from("then"), 3)],
+    ///     found_suggestions
+    /// );
+    /// ```
+    pub fn suggest(&self, word: &str, max_cost: u32, limit: usize) -> Vec<Suggestion> {
+        let query = get_characters(word).collect::<Vec<_>>();
+        let mut suggestions = self.candidates(&query, max_cost);
+
+        suggestions.sort_unstable_by(|suggestion, other| {
+            suggestion
+                .cost
+                .cmp(&other.cost)
+                .then(other.count.cmp(&suggestion.count))
+                .then_with(|| suggestion.word.cmp(&other.word))
+        });
+        suggestions.truncate(limit);
+
+        suggestions
+    }
+
+    /// Returns the number of distinct words in the dictionary.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if the dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Function returns every dictionary word at most 'max_cost' away from 'query'.
+    /// Rows of the distance table are kept for every node on the current path, where
+    /// a row holds the costs of turning every prefix of 'query' into the word of the node.
+    fn candidates(&self, query: &[char], max_cost: u32) -> Vec<Suggestion> {
+        let first_row = (0..=query.len() as u32)
+            .map(|j| j.saturating_mul(self.costs.deletion))
+            .collect::<Vec<_>>();
+
+        let mut rows = vec![first_row];
+        let mut word = Vec::new();
+        let mut found_suggestions = Vec::new();
+        let mut stack = children_of(self.words.root(), 1);
+
+        while let Some((character, node, depth)) = stack.pop() {
+            rows.truncate(depth);
+            word.truncate(depth - 1);
+
+            let row = self.next_row(query, &rows, &word, character);
+            word.push(character);
+
+            let cost = row[query.len()];
+            if node.is_associated() && cost <= max_cost {
+                found_suggestions.push(Suggestion {
+                    word: word.iter().collect(),
+                    cost,
+                    count: node.word_count(),
+                });
+            }
+
+            // A transposition at the next node reaches back to the parent row,
+            // so a subtree is only hopeless once both rows are over the limit.
+            let min_cost = row.iter().copied().min().unwrap();
+            let min_parent_cost = rows[depth - 1].iter().copied().min().unwrap();
+            let is_reachable = min_cost <= max_cost
+                || min_parent_cost.saturating_add(self.costs.transposition) <= max_cost;
+
+            rows.push(row);
+
+            if is_reachable {
+                stack.extend(children_of(node, depth + 1));
+            }
+        }
+
+        found_suggestions
+    }
+
+    /// Function returns the row of the distance table for the word of the current path
+    /// extended with 'character', from the rows of the nodes on the path.
+    fn next_row(
+        &self,
+        query: &[char],
+        rows: &[Vec<u32>],
+        word: &[char],
+        character: char,
+    ) -> Vec<u32> {
+        let costs = &self.costs;
+        let depth = rows.len();
+        let previous = &rows[depth - 1];
+
+        let mut row = Vec::with_capacity(query.len() + 1);
+        row.push(previous[0].saturating_add(costs.insertion));
+
+        for j in 1..=query.len() {
+            let mut cost = previous[j]
+                .saturating_add(costs.insertion)
+                .min(row[j - 1].saturating_add(costs.deletion))
+                .min(
+                    previous[j - 1].saturating_add(self.substitution_cost(query[j - 1], character)),
+                );
+
+            let is_transposed = j >= 2
+                && depth >= 2
+                && query[j - 1] != character
+                && query[j - 1] == word[depth - 2]
+                && query[j - 2] == character;
+
+            if is_transposed {
+                cost = cost.min(rows[depth - 2][j - 2].saturating_add(costs.transposition));
+            }
+
+            row.push(cost);
+        }
+
+        row
+    }
+
+    /// Function returns the cost of typing 'typed' instead of 'intended'.
+    fn substitution_cost(&self, typed: char, intended: char) -> u32 {
+        if typed == intended {
+            0
+        } else if are_adjacent_keys(typed, intended) {
+            self.costs.adjacent_substitution
+        } else {
+            self.costs.substitution
+        }
+    }
+}
+
+impl From<CountingTrie> for SpellChecker {
+    /// Builds a spell checker with the default edit costs from a dictionary
+    /// where words are already counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{CountingTrie, SpellChecker};
+    /// let mut words = CountingTrie::new();
+    /// words.insert_n("word", 3);
+    ///
+    /// let checker = SpellChecker::from(words);
+    /// assert_eq!("word", checker.suggest("wird", 1, 1)[0].word());
+    /// ```
+    fn from(words: CountingTrie) -> Self {
+        SpellChecker {
+            words,
+            costs: EditCosts::default(),
+        }
+    }
+}
+
+/// Function returns the children of a node along with their characters and 'depth'.
+fn children_of(node: &TrieCountingNode, depth: usize) -> Vec<(char, &TrieCountingNode, usize)> {
+    node.children
+        .iter()
+        .map(|(&character, child)| (character, child, depth))
+        .collect()
+}
+
+/// Function returns the row and column of a letter on a QWERTY keyboard.
+fn key_position(character: char) -> Option<(usize, usize)> {
+    let character = character.to_ascii_lowercase();
+
+    QWERTY_ROWS
+        .iter()
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(character).map(|column| (row, column)))
+}
+
+/// Function returns true if two letters are next to each other on a QWERTY keyboard.
+/// A key touches the keys on its sides, and the two keys below it are the one
+/// with the same column and the one to the left of it.
+fn are_adjacent_keys(a: char, b: char) -> bool {
+    let (Some((row_a, column_a)), Some((row_b, column_b))) = (key_position(a), key_position(b))
+    else {
+        return false;
+    };
+
+    if row_a == row_b {
+        return column_a.abs_diff(column_b) == 1;
+    }
+
+    if row_a.abs_diff(row_b) != 1 {
+        return false;
+    }
+
+    let (upper, lower) = if row_a < row_b {
+        (column_a, column_b)
+    } else {
+        (column_b, column_a)
+    };
+
+    lower == upper || lower + 1 == upper
+}
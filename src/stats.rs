@@ -0,0 +1,82 @@
+use crate::child_storage::ChildStorage;
+use crate::trie_node::TrieNode;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Report on the structure of a trie and an estimate of its memory usage,
+/// for deciding how to store dictionaries.
+///
+/// The depth of a node is the number of characters on the path to it,
+/// so the root is on depth 0.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TrieStats {
+    /// Number of nodes, including the root.
+    pub node_count: usize,
+    /// Number of words.
+    pub word_count: usize,
+    /// Depth of the deepest node.
+    pub max_depth: usize,
+    /// Average depth of the nodes that words end on.
+    pub average_depth: f64,
+    /// Number of nodes by their number of children: the value on index 'i'
+    /// is the number of nodes with 'i' children.
+    pub branching_histogram: Vec<usize>,
+    /// Number of nodes storing their children as `Empty`.
+    pub empty_storage_nodes: usize,
+    /// Number of nodes storing their children as `Small`.
+    pub small_storage_nodes: usize,
+    /// Number of nodes storing their children as `SortedSmall`.
+    pub sorted_small_storage_nodes: usize,
+    /// Number of nodes storing their children as `Large`.
+    pub large_storage_nodes: usize,
+    /// Number of nodes storing their children as `DenseAscii`.
+    pub dense_ascii_storage_nodes: usize,
+    /// Estimate of the heap memory held by the trie in bytes. Memory that
+    /// the stored data holds on the heap by itself isn't counted.
+    pub heap_bytes: usize,
+}
+
+impl TrieStats {
+    /// Function visits every node of the trie with the given root and
+    /// collects the statistics of it.
+    pub(crate) fn collect<N: TrieNode>(root: &N) -> Self {
+        let mut stats = TrieStats::default();
+        let mut depth_sum = 0;
+        let mut stack = vec![(root, 0)];
+
+        while let Some((node, depth)) = stack.pop() {
+            let children = node.children();
+
+            stats.node_count += 1;
+            stats.max_depth = stats.max_depth.max(depth);
+
+            if node.is_associated() {
+                stats.word_count += 1;
+                depth_sum += depth;
+            }
+
+            if stats.branching_histogram.len() <= children.len() {
+                stats.branching_histogram.resize(children.len() + 1, 0);
+            }
+            stats.branching_histogram[children.len()] += 1;
+
+            match children {
+                ChildStorage::Empty => stats.empty_storage_nodes += 1,
+                ChildStorage::Small(_) => stats.small_storage_nodes += 1,
+                ChildStorage::SortedSmall(_) => stats.sorted_small_storage_nodes += 1,
+                ChildStorage::Large(_) => stats.large_storage_nodes += 1,
+                ChildStorage::DenseAscii(_) => stats.dense_ascii_storage_nodes += 1,
+            }
+
+            stats.heap_bytes += children.heap_bytes() + node.association_heap_bytes();
+
+            stack.extend(children.values().map(|child| (child, depth + 1)));
+        }
+
+        if stats.word_count > 0 {
+            stats.average_depth = depth_sum as f64 / stats.word_count as f64;
+        }
+
+        stats
+    }
+}
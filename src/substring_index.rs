@@ -0,0 +1,221 @@
+use crate::DataTrie;
+use crate::trie::get_characters;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Index for finding words by any fragment they contain, not only by their prefix.
+///
+/// Every suffix of an inserted word is kept in a data trie, with the identifier
+/// of the original word as its data. A fragment contained in a word is a prefix
+/// of one of its suffixes, so searching for it is a regular prefix search.
+/// A word of n characters inserts n suffixes, so this trades memory for lookup speed.
+#[derive(Debug, Default)]
+pub struct SubstringIndex {
+    suffixes: DataTrie<usize>,
+    words: Vec<Option<String>>,
+    free_ids: Vec<usize>,
+    len: usize,
+}
+
+impl SubstringIndex {
+    /// Returns a new instance of the index.
+    pub fn new() -> Self {
+        SubstringIndex {
+            suffixes: DataTrie::new(),
+            words: Vec::new(),
+            free_ids: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Insert a word and all of its suffixes into the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("iphone");
+    /// assert_eq!(vec!["iphone"], index.find_containing("hon"));
+    /// ```
+    pub fn insert(&mut self, word: &str) {
+        if self.contains(word) {
+            return;
+        }
+
+        let word = get_characters(word).collect::<String>();
+
+        let id = match self.free_ids.pop() {
+            Some(id) => id,
+            None => {
+                self.words.push(None);
+                self.words.len() - 1
+            }
+        };
+
+        for suffix in suffixes(&word) {
+            self.suffixes.insert(suffix, id);
+        }
+
+        self.words[id] = Some(word);
+        self.len += 1;
+    }
+
+    /// Removes a word and all of its suffixes from the index.
+    /// Suffixes shared with other words are kept for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("phone");
+    /// index.insert("iphone");
+    ///
+    /// index.remove("iphone");
+    /// assert_eq!(vec!["phone"], index.find_containing("hon"));
+    /// ```
+    pub fn remove(&mut self, word: &str) {
+        let Some(id) = self.find_id(word) else {
+            return;
+        };
+
+        let word = self.words[id].take().unwrap();
+
+        // Suffixes left without identifiers aren't a part of any word anymore.
+        for suffix in suffixes(&word) {
+            self.suffixes
+                .remove_data_where(suffix, |&suffix_id| suffix_id == id, true);
+        }
+
+        self.free_ids.push(id);
+        self.len -= 1;
+    }
+
+    /// Returns all words that contain 'fragment' anywhere in them.
+    /// Order is not guaranteed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("iphone");
+    /// index.insert("microphone");
+    /// index.insert("phantom");
+    ///
+    /// let mut found_words = index.find_containing("phon");
+    /// found_words.sort();
+    /// assert_eq!(vec!["iphone", "microphone"], found_words);
+    /// ```
+    pub fn find_containing(&self, fragment: &str) -> Vec<&str> {
+        let mut ids = self
+            .suffixes
+            .get_data(fragment, true)
+            .unwrap_or_default()
+            .into_iter()
+            .copied()
+            .collect::<Vec<_>>();
+
+        // A word containing the fragment more than once is found through every such suffix.
+        ids.sort_unstable();
+        ids.dedup();
+
+        ids.into_iter()
+            .filter_map(|id| self.words[id].as_deref())
+            .collect()
+    }
+
+    /// Returns true if the index contains 'word' as a whole word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("iphone");
+    /// assert!(index.contains("iphone"));
+    /// assert!(!index.contains("phone"));
+    /// ```
+    pub fn contains(&self, word: &str) -> bool {
+        self.find_id(word).is_some()
+    }
+
+    /// Returns the number of words in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("iphone");
+    /// index.insert("microphone");
+    /// assert_eq!(2, index.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no words are in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("iphone");
+    /// index.remove("iphone");
+    /// assert!(index.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all words from the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::SubstringIndex;
+    /// let mut index = SubstringIndex::new();
+    ///
+    /// index.insert("iphone");
+    /// index.clear();
+    /// assert!(index.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.suffixes.clear();
+        self.words.clear();
+        self.free_ids.clear();
+        self.len = 0;
+    }
+
+    /// Function for getting the identifier of a whole word. The whole word is
+    /// the longest of its suffixes, so its identifier is in that suffix's data.
+    fn find_id(&self, word: &str) -> Option<usize> {
+        let word = get_characters(word).collect::<String>();
+
+        self.suffixes
+            .get_data(&word, false)?
+            .into_iter()
+            .copied()
+            .find(|&id| self.words[id].as_deref() == Some(word.as_str()))
+    }
+}
+
+/// Function returns every non-empty suffix of a word, or only the
+/// empty suffix for the empty word.
+fn suffixes(word: &str) -> impl Iterator<Item = &str> {
+    let starts = word.char_indices().map(|(start, _)| start);
+    let empty_word_start = word.is_empty().then_some(0);
+
+    starts
+        .chain(empty_word_start)
+        .map(move |start| &word[start..])
+}
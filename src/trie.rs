@@ -1,18 +1,36 @@
 #[cfg(feature = "data")]
+mod arena_data_trie;
+#[cfg(feature = "data")]
 mod data_trie;
+#[cfg(feature = "data")]
+mod phrase_trie;
+#[cfg(feature = "data")]
+mod trie_map;
 
+#[cfg(feature = "data")]
+pub use arena_data_trie::ArenaDataTrie;
 #[cfg(feature = "data")]
 pub use data_trie::DataTrie;
+#[cfg(feature = "data")]
+pub(crate) use phrase_trie::word_tokens;
+#[cfg(feature = "data")]
+pub use phrase_trie::{PhraseTrie, Tokenizer};
+#[cfg(feature = "data")]
+pub use trie_map::TrieMap;
 
+mod arena_trie;
+mod counting_trie;
 mod regular_trie;
 
+pub use arena_trie::ArenaTrie;
+pub use counting_trie::CountingTrie;
 pub use regular_trie::Trie;
 
 #[cfg(feature = "unicode")]
 use unicode_normalization::UnicodeNormalization;
 
 #[cfg(not(feature = "unicode"))]
-use std::str::Chars;
+use core::str::Chars;
 
 #[cfg(feature = "unicode")]
 pub fn get_characters(word: &str) -> impl Iterator<Item = char> + '_ {
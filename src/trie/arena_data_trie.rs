@@ -0,0 +1,562 @@
+use crate::TrieError;
+use crate::trie::get_characters;
+use crate::trie_node::NodeArena;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops;
+
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+use thin_vec::{ThinVec, thin_vec};
+
+/// Data trie whose nodes all live in a single vector and refer to their children
+/// by index. Building it doesn't allocate for every node, and clearing
+/// or dropping it frees a single buffer, apart from the data of its words.
+/// Words and their data are always returned in lexicographic order of the words.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug)]
+pub struct ArenaDataTrie<D> {
+    arena: NodeArena<D>,
+    len: usize,
+}
+
+impl<D> Default for ArenaDataTrie<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> ArenaDataTrie<D> {
+    /// Returns a new instance of the trie.
+    pub fn new() -> Self {
+        Self::with_capacity(1)
+    }
+
+    /// Returns a new instance of the trie with room for 'capacity'
+    /// nodes before the arena needs to grow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::with_capacity(64);
+    ///
+    /// trie.insert("word", "somedata");
+    /// assert!(trie.contains("word"));
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaDataTrie {
+            arena: NodeArena::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Insert a word into the trie, with the corresponding data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word1", "somedata");
+    /// assert_eq!(vec![String::from("word1")], trie.get_all());
+    /// ```
+    pub fn insert(&mut self, word: &str, associated_data: D) {
+        let last = self.arena.find_or_insert(get_characters(word));
+
+        match self.arena.data_mut(last) {
+            Some(data_vec) => data_vec.push(associated_data),
+            data @ None => {
+                *data = Some(thin_vec![associated_data]);
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Insert a word into the trie, with the corresponding data, reporting
+    /// TrieError::CapacityExceeded instead of panicking when the arena can't
+    /// hold the nodes of the word. Nothing is inserted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word1", "somedata"));
+    /// assert_eq!(vec![&"somedata"], trie.get_data("word1", false).unwrap());
+    /// ```
+    pub fn try_insert(&mut self, word: &str, associated_data: D) -> Result<(), TrieError> {
+        let last = self.arena.try_find_or_insert(get_characters(word))?;
+
+        match self.arena.data_mut(last) {
+            Some(data_vec) => data_vec.push(associated_data),
+            data @ None => {
+                *data = Some(thin_vec![associated_data]);
+                self.len += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert a word into the trie, with no corresponding data.
+    /// This enables later attachment of data onto the inserted word.
+    /// Type of trie must be annotated if this is the first function call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::<&str>::new();
+    ///
+    /// trie.insert_no_data("word1");
+    /// assert_eq!(vec![String::from("word1")], trie.get_all());
+    ///
+    /// trie.insert("word1", "somedata");
+    /// assert_eq!(vec![&"somedata"], trie.get_data("word1", false).unwrap());
+    /// ```
+    pub fn insert_no_data(&mut self, word: &str) {
+        let last = self.arena.find_or_insert(get_characters(word));
+        let data = self.arena.data_mut(last);
+
+        if data.is_none() {
+            *data = Some(ThinVec::new());
+            self.len += 1;
+        }
+    }
+
+    /// Removes a word from the trie and returns data associated with that word.
+    /// If the word is a prefix to some word, some word isn't removed from the trie.
+    /// If the word is not found, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word", "somedata");
+    /// trie.insert("wording", "somedata2");
+    ///
+    /// assert_eq!(vec!["somedata"], trie.remove("word").unwrap());
+    /// assert_eq!(vec![String::from("wording")], trie.get("word").unwrap());
+    /// assert_eq!(None, trie.remove("word"));
+    /// ```
+    pub fn remove(&mut self, word: &str) -> Option<Vec<D>> {
+        let data_vec = self.arena.remove_word(get_characters(word))?;
+        self.len -= 1;
+
+        Some(data_vec.into_iter().collect())
+    }
+
+    /// Removes a word from the trie and returns data associated with that word,
+    /// reporting why the word couldn't be removed.
+    /// If no word begins with 'word', TrieError::NotFound is returned.
+    /// If the word is only a prefix to some word, TrieError::NotAWord is returned
+    /// and nothing is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{ArenaDataTrie, TrieError};
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("wording", "somedata");
+    ///
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_remove("word"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_remove("other"));
+    /// assert_eq!(Ok(vec!["somedata"]), trie.try_remove("wording"));
+    /// ```
+    pub fn try_remove(&mut self, word: &str) -> Result<Vec<D>, TrieError> {
+        self.arena.find_word(get_characters(word))?;
+
+        Ok(self.remove(word).unwrap_or_default())
+    }
+
+    /// Removes every word that begins with 'prefix' and collects all removed data.
+    /// Not including the word 'prefix' if it's present.
+    /// If the sequence 'prefix' is not found, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("eat", "somedata");
+    /// trie.insert("eats", "somedata2");
+    /// trie.insert("eating", "somedata3");
+    /// trie.insert("ea", "somedata4");
+    ///
+    /// let mut removed_data = trie.remove_prefix("ea").unwrap();
+    /// removed_data.sort();
+    ///
+    /// assert_eq!(vec![String::from("ea")], trie.get_all());
+    /// assert_eq!(vec!["somedata", "somedata2", "somedata3"], removed_data);
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) -> Option<Vec<D>> {
+        let current = self.arena.find(get_characters(prefix))?;
+
+        let mut data_vec = Vec::new();
+        self.len -= self
+            .arena
+            .remove_descendants(current, |data| data_vec.push(data));
+
+        Some(data_vec)
+    }
+
+    /// Returns a vector of references to data of some word or references
+    /// to all found data of some word prefix when 'soft_match' is set to true.
+    /// If the word is not found and 'soft_match' is set to false, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word1", "somedata");
+    /// trie.insert("word2", "somemoredata");
+    ///
+    /// assert_eq!(vec![&"somedata"], trie.get_data("word1", false).unwrap());
+    /// assert_eq!(vec![&"somedata", &"somemoredata"], trie.get_data("word", true).unwrap());
+    /// ```
+    pub fn get_data(&self, query: &str, soft_match: bool) -> Option<Vec<&D>> {
+        let current = self.arena.find(get_characters(query))?;
+
+        if soft_match {
+            Some(self.arena.subtree_data(current))
+        } else {
+            self.arena
+                .data(current)
+                .map(|data_vec| data_vec.iter().collect())
+        }
+    }
+
+    /// Returns a vector of mutable references to data of some word that equals 'query'
+    /// or mutable references to all found data of words that begin with 'query'
+    /// when 'soft_match' is set to true.
+    /// If the word is not found and 'soft_match' is set to false, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word1", "somedata");
+    /// trie.insert("word2", "somemoredata");
+    ///
+    /// *trie.get_data_mut("word1", false).unwrap()[0] = "changeddata";
+    /// assert_eq!(vec![&"changeddata"], trie.get_data("word1", false).unwrap());
+    ///
+    /// for data in trie.get_data_mut("word", true).unwrap() {
+    ///     *data = "samedata";
+    /// }
+    /// assert_eq!(vec![&"samedata", &"samedata"], trie.get_data("word", true).unwrap());
+    /// ```
+    pub fn get_data_mut(&mut self, query: &str, soft_match: bool) -> Option<Vec<&mut D>> {
+        let current = self.arena.find(get_characters(query))?;
+
+        if soft_match {
+            Some(self.arena.subtree_data_mut(current))
+        } else {
+            self.arena
+                .data_mut(current)
+                .as_mut()
+                .map(|data_vec| data_vec.iter_mut().collect())
+        }
+    }
+
+    /// Clears and returns data of some word. If the word is not found returns None.
+    /// If there is no data associated to the word, an empty vector is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word", "data1");
+    /// trie.insert("word", "data2");
+    /// let found_data = trie.clear_data("word");
+    ///
+    /// assert_eq!(Vec::<&&str>::new(), trie.get_data("word", false).unwrap());
+    /// assert_eq!(vec!["data1", "data2"], found_data.unwrap());
+    /// ```
+    pub fn clear_data(&mut self, word: &str) -> Option<Vec<D>> {
+        let current = self.arena.find(get_characters(word))?;
+
+        self.arena
+            .data_mut(current)
+            .as_mut()
+            .map(|data_vec| core::mem::take(data_vec).into_iter().collect())
+    }
+
+    /// Returns an option enum with a vector of owned strings
+    /// representing all found words that begin with 'query',
+    /// in lexicographic order.
+    /// If the word 'query' doesn't exist, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word2", "somedata");
+    /// trie.insert("word1", "somedata");
+    ///
+    /// let all_correct_words = vec![String::from("word1"), String::from("word2")];
+    /// assert_eq!(all_correct_words, trie.get("word").unwrap());
+    /// ```
+    pub fn get(&self, query: &str) -> Option<Vec<String>> {
+        let current = self.arena.find(get_characters(query))?;
+
+        Some(
+            self.arena
+                .words(current, get_characters(query).collect())
+                .into_iter()
+                .map(|(word, _)| word)
+                .collect(),
+        )
+    }
+
+    /// Returns the vector of longest words found in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("shortwrd", 1);
+    /// trie.insert("verylongword", 2);
+    /// trie.insert("somelongword", 3);
+    ///
+    /// let longest_words = vec![String::from("somelongword"), String::from("verylongword")];
+    /// assert_eq!(longest_words, trie.get_longest());
+    /// ```
+    pub fn get_longest(&self) -> Vec<String> {
+        self.arena.words_min_max(true)
+    }
+
+    /// Returns the vector of shortest words found in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("shortwrd", 1);
+    /// trie.insert("rlyshort", 2);
+    /// trie.insert("verylongword", 3);
+    ///
+    /// let shortest_word = vec![String::from("rlyshort"), String::from("shortwrd")];
+    /// assert_eq!(shortest_word, trie.get_shortest());
+    /// ```
+    pub fn get_shortest(&self) -> Vec<String> {
+        self.arena.words_min_max(false)
+    }
+
+    /// Returns the number of words in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word1", 1);
+    /// trie.insert("word2", 2);
+    /// trie.insert("word2", 3);
+    /// assert_eq!(2, trie.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of words that start with 'prefix'.
+    /// Not including the word 'prefix' if it's present.
+    ///
+    /// # Examples
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word1", 1);
+    /// trie.insert("word2", 2);
+    /// trie.insert("word", 3);
+    /// assert_eq!(2, trie.len_prefix("word"));
+    /// ```
+    pub fn len_prefix(&self, prefix: &str) -> usize {
+        match self.arena.find(get_characters(prefix)) {
+            None => 0,
+            Some(node) => self.arena.count_words(node) - self.arena.is_associated(node) as usize,
+        }
+    }
+
+    /// Returns a vector of owned strings representing
+    /// all words in the trie, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word2", 2);
+    /// trie.insert("word1", 1);
+    ///
+    /// assert_eq!(vec![String::from("word1"), String::from("word2")], trie.get_all());
+    /// ```
+    pub fn get_all(&self) -> Vec<String> {
+        self.get("").unwrap()
+    }
+
+    /// Returns true if the trie contains 'query' as a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word", 1);
+    /// assert!(trie.contains("word"));
+    /// assert!(!trie.contains("notfound"));
+    /// ```
+    pub fn contains(&self, query: &str) -> bool {
+        self.arena
+            .find(get_characters(query))
+            .is_some_and(|node| self.arena.is_associated(node))
+    }
+
+    /// Returns true if no words are in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word", 1);
+    /// trie.remove("word");
+    ///
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all words from the trie.
+    /// The arena keeps its buffer for the words inserted afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie = ArenaDataTrie::new();
+    ///
+    /// trie.insert("word1", 1);
+    /// trie.insert("word2", 2);
+    ///
+    /// trie.clear();
+    /// assert!(trie.is_empty());
+    /// assert_eq!(0, trie.len());
+    /// ```
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.len = 0;
+    }
+}
+
+impl<D> ops::Add for ArenaDataTrie<D> {
+    type Output = ArenaDataTrie<D>;
+
+    /// Operation + merges two tries, appending the data of duplicate words.
+    /// The smaller trie is always added to the larger one for efficiency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie_1 = ArenaDataTrie::new();
+    /// trie_1.insert("word1", 1);
+    /// trie_1.insert("word", 2);
+    ///
+    /// let mut trie_2 = ArenaDataTrie::new();
+    /// trie_2.insert("word", 3);
+    ///
+    /// let trie_3 = trie_1 + trie_2;
+    ///
+    /// assert_eq!(2, trie_3.len());
+    /// assert_eq!(vec![&2, &3], trie_3.get_data("word", false).unwrap());
+    /// ```
+    fn add(self, rhs: Self) -> Self::Output {
+        let (smaller, mut bigger) = if self.len < rhs.len {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        bigger += smaller;
+        bigger
+    }
+}
+
+impl<D> ops::AddAssign for ArenaDataTrie<D> {
+    /// Operation += merges two tries, appending the data of duplicate words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut trie_1 = ArenaDataTrie::new();
+    /// trie_1.insert("word1", 1);
+    /// trie_1.insert("word", 2);
+    ///
+    /// let mut trie_2 = ArenaDataTrie::new();
+    /// trie_2.insert("word2", 3);
+    /// trie_2.insert("word", 4);
+    ///
+    /// trie_1 += trie_2;
+    ///
+    /// assert_eq!(3, trie_1.len());
+    /// assert_eq!(vec![&2, &4], trie_1.get_data("word", false).unwrap());
+    /// ```
+    fn add_assign(&mut self, rhs: Self) {
+        self.len += self.arena.merge(rhs.arena);
+    }
+}
+
+impl<D: PartialEq> PartialEq for ArenaDataTrie<D> {
+    /// Operation '==' can be applied only to tries whose data implements PartialEq.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaDataTrie;
+    /// let mut data_trie_1 = ArenaDataTrie::new();
+    /// data_trie_1.insert("test", 1);
+    ///
+    /// let mut data_trie_2 = ArenaDataTrie::new();
+    /// data_trie_2.insert("test", 1);
+    ///
+    /// assert_eq!(data_trie_1, data_trie_2);
+    ///
+    /// data_trie_2.insert("test2", 2);
+    ///
+    /// assert_ne!(data_trie_1, data_trie_2);
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.arena == other.arena
+    }
+}
@@ -0,0 +1,448 @@
+use crate::TrieError;
+use crate::trie::get_characters;
+use crate::trie_node::NodeArena;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops;
+
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+use thin_vec::ThinVec;
+
+/// Trie whose nodes all live in a single vector and refer to their children
+/// by index. Building it doesn't allocate for every node, and clearing
+/// or dropping it frees a single buffer.
+/// Words are always returned in lexicographic order.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug)]
+pub struct ArenaTrie {
+    arena: NodeArena<()>,
+    len: usize,
+}
+
+impl Default for ArenaTrie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArenaTrie {
+    /// Returns a new instance of the trie.
+    pub fn new() -> Self {
+        Self::with_capacity(1)
+    }
+
+    /// Returns a new instance of the trie with room for 'capacity'
+    /// nodes before the arena needs to grow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::with_capacity(64);
+    ///
+    /// trie.insert("word");
+    /// assert!(trie.contains("word"));
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaTrie {
+            arena: NodeArena::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Insert a word into the trie, with no corresponding data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    ///
+    /// assert_eq!(vec![String::from("word1"), String::from("word2")], trie.get_all());
+    /// ```
+    pub fn insert(&mut self, word: &str) {
+        let last = self.arena.find_or_insert(get_characters(word));
+        let data = self.arena.data_mut(last);
+
+        if data.is_none() {
+            *data = Some(ThinVec::new());
+            self.len += 1;
+        }
+    }
+
+    /// Insert a word into the trie, with no corresponding data, reporting
+    /// TrieError::CapacityExceeded instead of panicking when the arena can't
+    /// hold the nodes of the word. Nothing is inserted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word"));
+    /// assert!(trie.contains("word"));
+    /// ```
+    pub fn try_insert(&mut self, word: &str) -> Result<(), TrieError> {
+        let last = self.arena.try_find_or_insert(get_characters(word))?;
+        let data = self.arena.data_mut(last);
+
+        if data.is_none() {
+            *data = Some(ThinVec::new());
+            self.len += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a word from the trie.
+    /// If the word is a prefix to some word, some word isn't removed from the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.insert("wording");
+    ///
+    /// trie.remove("word");
+    /// assert_eq!(vec![String::from("wording")], trie.get("word").unwrap());
+    ///
+    /// trie.remove("wording");
+    /// assert_eq!(Vec::<String>::new(), trie.get_all());
+    /// ```
+    pub fn remove(&mut self, word: &str) {
+        if self.arena.remove_word(get_characters(word)).is_some() {
+            self.len -= 1;
+        }
+    }
+
+    /// Removes a word from the trie, reporting why the word couldn't be removed.
+    /// If no word begins with 'word', TrieError::NotFound is returned.
+    /// If the word is only a prefix to some word, TrieError::NotAWord is returned
+    /// and nothing is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{ArenaTrie, TrieError};
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("wording");
+    ///
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_remove("word"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_remove("other"));
+    /// assert_eq!(Ok(()), trie.try_remove("wording"));
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn try_remove(&mut self, word: &str) -> Result<(), TrieError> {
+        self.arena.find_word(get_characters(word))?;
+
+        self.arena.remove_word(get_characters(word));
+        self.len -= 1;
+
+        Ok(())
+    }
+
+    /// Removes every word that begins with 'prefix'.
+    /// Not including the word 'prefix' if it's present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("eat");
+    /// trie.insert("eats");
+    /// trie.insert("eating");
+    /// trie.insert("ea");
+    ///
+    /// trie.remove_prefix("ea");
+    ///
+    /// assert_eq!(vec![String::from("ea")], trie.get_all());
+    /// ```
+    pub fn remove_prefix(&mut self, prefix: &str) {
+        let Some(current) = self.arena.find(get_characters(prefix)) else {
+            return;
+        };
+
+        self.len -= self.arena.remove_descendants(current, |_| {});
+    }
+
+    /// Returns an option enum with a vector of owned strings
+    /// representing all found words that begin with 'query',
+    /// in lexicographic order.
+    /// If the word 'query' doesn't exist, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word2");
+    /// trie.insert("word1");
+    ///
+    /// let all_correct_words = vec![String::from("word1"), String::from("word2")];
+    /// assert_eq!(all_correct_words, trie.get("word").unwrap());
+    /// ```
+    pub fn get(&self, query: &str) -> Option<Vec<String>> {
+        let current = self.arena.find(get_characters(query))?;
+
+        Some(
+            self.arena
+                .words(current, get_characters(query).collect())
+                .into_iter()
+                .map(|(word, _)| word)
+                .collect(),
+        )
+    }
+
+    /// Returns the vector of longest words found in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("shortwrd");
+    /// trie.insert("verylongword");
+    /// trie.insert("somelongword");
+    ///
+    /// let longest_words = vec![String::from("somelongword"), String::from("verylongword")];
+    /// assert_eq!(longest_words, trie.get_longest());
+    /// ```
+    pub fn get_longest(&self) -> Vec<String> {
+        self.arena.words_min_max(true)
+    }
+
+    /// Returns the vector of shortest words found in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("shortwrd");
+    /// trie.insert("rlyshort");
+    /// trie.insert("verylongword");
+    ///
+    /// let shortest_word = vec![String::from("rlyshort"), String::from("shortwrd")];
+    /// assert_eq!(shortest_word, trie.get_shortest());
+    /// ```
+    pub fn get_shortest(&self) -> Vec<String> {
+        self.arena.words_min_max(false)
+    }
+
+    /// Returns the number of words in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    /// assert_eq!(2, trie.len());
+    ///
+    /// trie.remove("word1");
+    /// assert_eq!(1, trie.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of words that start with 'prefix'.
+    /// Not including the word 'prefix' if it's present.
+    ///
+    /// # Examples
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    /// trie.insert("word");
+    /// assert_eq!(2, trie.len_prefix("word"));
+    /// ```
+    pub fn len_prefix(&self, prefix: &str) -> usize {
+        match self.arena.find(get_characters(prefix)) {
+            None => 0,
+            Some(node) => self.arena.count_words(node) - self.arena.is_associated(node) as usize,
+        }
+    }
+
+    /// Returns a vector of owned strings representing
+    /// all words in the trie, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word2");
+    /// trie.insert("word1");
+    ///
+    /// assert_eq!(vec![String::from("word1"), String::from("word2")], trie.get_all());
+    /// ```
+    pub fn get_all(&self) -> Vec<String> {
+        self.get("").unwrap()
+    }
+
+    /// Returns true if the trie contains 'query' as a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word");
+    /// assert!(trie.contains("word"));
+    /// assert!(!trie.contains("notfound"));
+    /// ```
+    pub fn contains(&self, query: &str) -> bool {
+        self.arena
+            .find(get_characters(query))
+            .is_some_and(|node| self.arena.is_associated(node))
+    }
+
+    /// Returns true if no words are in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.remove("word");
+    ///
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all words from the trie.
+    /// The arena keeps its buffer for the words inserted afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie = ArenaTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    ///
+    /// trie.clear();
+    /// assert!(trie.is_empty());
+    /// assert_eq!(0, trie.len());
+    /// ```
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.len = 0;
+    }
+}
+
+impl ops::Add for ArenaTrie {
+    type Output = ArenaTrie;
+
+    /// Operation + merges two tries, leaving out duplicate words.
+    /// The smaller trie is always added to the larger one for efficiency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie_1 = ArenaTrie::new();
+    /// trie_1.insert("word1");
+    /// trie_1.insert("word");
+    ///
+    /// let mut trie_2 = ArenaTrie::new();
+    /// trie_2.insert("word2");
+    /// trie_2.insert("word");
+    ///
+    /// let mut correct = ArenaTrie::new();
+    /// correct.insert("word");
+    /// correct.insert("word1");
+    /// correct.insert("word2");
+    ///
+    /// let trie_3 = trie_1 + trie_2;
+    ///
+    /// assert_eq!(trie_3, correct);
+    /// ```
+    fn add(self, rhs: Self) -> Self::Output {
+        let (smaller, mut bigger) = if self.len < rhs.len {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        bigger += smaller;
+        bigger
+    }
+}
+
+impl ops::AddAssign for ArenaTrie {
+    /// Operation += merges two tries, leaving out duplicate words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie_1 = ArenaTrie::new();
+    /// trie_1.insert("word1");
+    /// trie_1.insert("word");
+    ///
+    /// let mut trie_2 = ArenaTrie::new();
+    /// trie_2.insert("word2");
+    /// trie_2.insert("word");
+    ///
+    /// trie_1 += trie_2;
+    ///
+    /// assert_eq!(3, trie_1.len());
+    /// assert!(trie_1.contains("word2"));
+    /// ```
+    fn add_assign(&mut self, rhs: Self) {
+        self.len += self.arena.merge(rhs.arena);
+    }
+}
+
+impl PartialEq for ArenaTrie {
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::ArenaTrie;
+    /// let mut trie_1 = ArenaTrie::new();
+    /// trie_1.insert("test");
+    ///
+    /// let mut trie_2 = ArenaTrie::new();
+    /// trie_2.insert("test");
+    ///
+    /// assert_eq!(trie_1, trie_2);
+    ///
+    /// trie_2.insert("test2");
+    ///
+    /// assert_ne!(trie_1, trie_2);
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.arena == other.arena
+    }
+}
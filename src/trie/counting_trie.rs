@@ -0,0 +1,467 @@
+use alloc::collections::BinaryHeap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use crate::child_storage::relayout_tree;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+use crate::StorageLayout;
+use crate::TrieError;
+use crate::trie::get_characters;
+use crate::trie_node::TrieCountingNode;
+
+/// Trie with multiset semantics: every word keeps the number of times it was inserted.
+/// Every node caches the total number of occurrences in its subtree,
+/// so counting occurrences under a prefix is a walk to the prefix node only.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", from = "CountingTrieFields")
+)]
+#[derive(Debug, Default)]
+pub struct CountingTrie {
+    root: TrieCountingNode,
+    len: usize,
+    layout: StorageLayout,
+}
+
+/// Fields of a deserialized trie, whose children are laid out
+/// again with the layout of the trie.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(crate = "serde_crate")]
+struct CountingTrieFields {
+    root: TrieCountingNode,
+    len: usize,
+    layout: StorageLayout,
+}
+
+#[cfg(feature = "serde")]
+impl From<CountingTrieFields> for CountingTrie {
+    fn from(mut fields: CountingTrieFields) -> Self {
+        relayout_tree(&mut fields.root, fields.layout, |node| &mut node.children);
+
+        CountingTrie {
+            root: fields.root,
+            len: fields.len,
+            layout: fields.layout,
+        }
+    }
+}
+
+impl CountingTrie {
+    /// Returns a new instance of the trie.
+    pub fn new() -> Self {
+        Self::with_layout(StorageLayout::default())
+    }
+
+    /// Returns a new instance of the trie that stores the children
+    /// of its nodes according to 'layout'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{CountingTrie, StorageLayout};
+    /// let layout = StorageLayout {
+    ///     promotion_threshold: 8,
+    ///     sorted_small: true,
+    ///     dense_ascii: false,
+    /// };
+    /// let mut trie = CountingTrie::with_layout(layout);
+    ///
+    /// trie.insert("word");
+    /// assert_eq!(1, trie.count("word"));
+    /// assert_eq!(layout, trie.layout());
+    /// ```
+    pub fn with_layout(layout: StorageLayout) -> Self {
+        CountingTrie {
+            root: TrieCountingNode::new(),
+            len: 0,
+            layout,
+        }
+    }
+
+    /// Returns the layout the trie stores the children of its nodes with.
+    pub fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
+    /// Insert one occurrence of a word into the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.insert("word");
+    /// assert_eq!(2, trie.count("word"));
+    /// assert_eq!(1, trie.len());
+    /// ```
+    pub fn insert(&mut self, word: &str) {
+        self.insert_n(word, 1);
+    }
+
+    /// Insert 'n' occurrences of a word into the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert_n("word", 5);
+    /// assert_eq!(5, trie.count("word"));
+    /// ```
+    pub fn insert_n(&mut self, word: &str, n: usize) {
+        if n == 0 {
+            return;
+        }
+
+        if self
+            .root
+            .add_occurrences(get_characters(word), n, self.layout)
+            == 0
+        {
+            self.len += 1;
+        }
+    }
+
+    /// Removes one occurrence of a word from the trie.
+    /// The word itself is removed once its last occurrence is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.insert("word");
+    ///
+    /// trie.remove("word");
+    /// assert_eq!(1, trie.count("word"));
+    ///
+    /// trie.remove("word");
+    /// assert!(!trie.contains("word"));
+    /// ```
+    pub fn remove(&mut self, word: &str) {
+        if self.count(word) == 0 {
+            return;
+        }
+
+        self.remove_occurrences(word, 1);
+    }
+
+    /// Removes one occurrence of a word from the trie,
+    /// reporting why no occurrence could be removed.
+    /// If no word begins with 'word', TrieError::NotFound is returned.
+    /// If the word is only a prefix to some word, TrieError::NotAWord is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{CountingTrie, TrieError};
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("wording");
+    ///
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_remove("word"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_remove("other"));
+    /// assert_eq!(Ok(()), trie.try_remove("wording"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_remove("wording"));
+    /// ```
+    pub fn try_remove(&mut self, word: &str) -> Result<(), TrieError> {
+        match self.get_final_node(word) {
+            None => Err(TrieError::NotFound),
+            Some(node) if !node.is_associated() => Err(TrieError::NotAWord),
+            Some(_) => {
+                self.remove_occurrences(word, 1);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes every occurrence of a word from the trie and
+    /// returns the number of removed occurrences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert_n("word", 3);
+    /// assert_eq!(3, trie.remove_all("word"));
+    /// assert_eq!(0, trie.remove_all("word"));
+    /// ```
+    pub fn remove_all(&mut self, word: &str) -> usize {
+        let count = self.count(word);
+
+        if count > 0 {
+            self.remove_occurrences(word, count);
+        }
+
+        count
+    }
+
+    /// Returns the number of occurrences of a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word");
+    /// assert_eq!(1, trie.count("word"));
+    /// assert_eq!(0, trie.count("notfound"));
+    /// ```
+    pub fn count(&self, word: &str) -> usize {
+        self.get_final_node(word)
+            .map_or(0, |node| node.word_count())
+    }
+
+    /// Returns the number of occurrences of all words in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    /// assert_eq!(3, trie.total_count());
+    /// ```
+    pub fn total_count(&self) -> usize {
+        self.root.subtree_count()
+    }
+
+    /// Returns the number of occurrences of words that start with 'prefix'.
+    /// Not including the occurrences of the word 'prefix' if it's present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.insert("word1");
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    /// assert_eq!(3, trie.count_prefix("word"));
+    /// ```
+    pub fn count_prefix(&self, prefix: &str) -> usize {
+        self.get_final_node(prefix)
+            .map_or(0, |node| node.subtree_count() - node.word_count())
+    }
+
+    /// Returns at most 'k' words with the most occurrences, along with
+    /// their occurrences, from the most to the least frequent.
+    /// Words with the same number of occurrences are ordered lexicographically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert_n("the", 10);
+    /// trie.insert_n("trie", 3);
+    /// trie.insert_n("a", 7);
+    ///
+    /// assert_eq!(
+    ///     vec![(String::from("the"), 10), (String::from("a"), 7)],
+    ///     trie.most_frequent(2)
+    /// );
+    /// ```
+    pub fn most_frequent(&self, k: usize) -> Vec<(String, usize)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.root.most_frequent(&mut String::new(), k, &mut heap);
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|entry| (entry.0.1.0, entry.0.0))
+            .collect()
+    }
+
+    /// Returns an option enum with a vector of owned strings and their
+    /// occurrences, representing all found words that begin with 'query'.
+    /// If the word 'query' doesn't exist, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    /// trie.insert("word2");
+    ///
+    /// let mut found_words = trie.get("word").unwrap();
+    /// found_words.sort();
+    /// assert_eq!(vec![(String::from("word1"), 1), (String::from("word2"), 2)], found_words);
+    /// ```
+    pub fn get(&self, query: &str) -> Option<Vec<(String, usize)>> {
+        let mut substring = String::new();
+        let mut current_node = &self.root;
+
+        for character in get_characters(query) {
+            current_node = current_node.children.get(character)?;
+            substring.push(character);
+        }
+
+        let mut words_vec = Vec::new();
+        current_node.find_words(&mut substring, &mut words_vec);
+
+        Some(words_vec)
+    }
+
+    /// Returns a vector of owned strings and their occurrences,
+    /// representing all words in the trie.
+    /// Order is not guaranteed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word1");
+    ///
+    /// assert_eq!(vec![(String::from("word1"), 2)], trie.get_all());
+    /// ```
+    pub fn get_all(&self) -> Vec<(String, usize)> {
+        self.get("").unwrap()
+    }
+
+    /// Returns the number of distinct words in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    /// assert_eq!(2, trie.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the trie contains 'query' as a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word");
+    /// assert!(trie.contains("word"));
+    /// assert!(!trie.contains("notfound"));
+    /// ```
+    pub fn contains(&self, query: &str) -> bool {
+        self.count(query) > 0
+    }
+
+    /// Returns true if no words are in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.remove("word");
+    ///
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all words from the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie = CountingTrie::new();
+    ///
+    /// trie.insert("word1");
+    /// trie.insert("word2");
+    ///
+    /// trie.clear();
+    /// assert!(trie.is_empty());
+    /// assert_eq!(0, trie.total_count());
+    /// ```
+    pub fn clear(&mut self) {
+        self.root = TrieCountingNode::new();
+        self.len = 0;
+    }
+
+    /// Function returns the root node, for walking the trie from outside of it.
+    pub(crate) fn root(&self) -> &TrieCountingNode {
+        &self.root
+    }
+
+    /// Function for removing 'n' occurrences of a word that has at least 'n' of them.
+    fn remove_occurrences(&mut self, word: &str, n: usize) {
+        if self.count(word) == n {
+            self.len -= 1;
+        }
+
+        self.root
+            .remove_occurrences(get_characters(word), n, self.layout);
+    }
+
+    /// Function for getting the last node in a character sequence.
+    fn get_final_node(&self, query: &str) -> Option<&TrieCountingNode> {
+        let mut current = &self.root;
+
+        for character in get_characters(query) {
+            current = current.children.get(character)?;
+        }
+
+        Some(current)
+    }
+}
+
+impl PartialEq for CountingTrie {
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::CountingTrie;
+    /// let mut trie_1 = CountingTrie::new();
+    /// trie_1.insert("test");
+    ///
+    /// let mut trie_2 = CountingTrie::new();
+    /// trie_2.insert("test");
+    ///
+    /// assert_eq!(trie_1, trie_2);
+    ///
+    /// trie_2.insert("test");
+    ///
+    /// assert_ne!(trie_1, trie_2);
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.root == other.root
+    }
+}
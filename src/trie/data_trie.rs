@@ -1,20 +1,76 @@
+use crate::Matcher;
+use crate::StorageLayout;
+use crate::TrieError;
+use crate::TrieLimits;
+use crate::TrieStats;
+use crate::limits::{Insertion, insertion_path};
+#[cfg(feature = "regex")]
+use crate::regex::find_matching;
+use crate::render;
+use crate::render::TreeDisplay;
 use crate::trie::get_characters;
-use crate::trie_node::TrieDataNode;
-use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::{fmt, ops};
+use crate::trie_node::{TrieDataNode, TrieNode, WordRange, count_nodes, find_words, words_min_max};
+#[cfg(feature = "subtree-counts")]
+use crate::trie_node::{rank, select};
+#[cfg(feature = "regex")]
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::ops::{Bound, RangeBounds};
+#[cfg(feature = "fst")]
+use core::str::Utf8Error;
+use core::{fmt, ops};
 
+#[cfg(feature = "fst")]
+use fst::Streamer;
+
+#[cfg(feature = "serde")]
+use crate::child_storage::relayout_tree;
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
 
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
-    serde(crate = "serde_crate")
+    serde(crate = "serde_crate", from = "DataTrieFields<D>")
 )]
 pub struct DataTrie<D> {
     root: TrieDataNode<D>,
     len: usize,
+    layout: StorageLayout,
+    limits: TrieLimits,
+    node_count: usize,
+}
+
+/// Fields of a deserialized trie, whose children are laid out
+/// again with the layout of the trie.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(crate = "serde_crate")]
+struct DataTrieFields<D> {
+    root: TrieDataNode<D>,
+    len: usize,
+    layout: StorageLayout,
+    limits: TrieLimits,
+    node_count: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<D> From<DataTrieFields<D>> for DataTrie<D> {
+    fn from(mut fields: DataTrieFields<D>) -> Self {
+        relayout_tree(&mut fields.root, fields.layout, |node| &mut node.children);
+
+        DataTrie {
+            root: fields.root,
+            len: fields.len,
+            layout: fields.layout,
+            limits: fields.limits,
+            node_count: fields.node_count,
+        }
+    }
 }
 
 impl<D> Default for DataTrie<D> {
@@ -26,13 +82,111 @@ impl<D> Default for DataTrie<D> {
 impl<D> DataTrie<D> {
     /// Returns a new instance of the trie.
     pub fn new() -> Self {
+        Self::with_layout(StorageLayout::default())
+    }
+
+    /// Returns a new instance of the trie that stores the children
+    /// of its nodes according to 'layout'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, StorageLayout};
+    /// let layout = StorageLayout {
+    ///     promotion_threshold: 8,
+    ///     sorted_small: true,
+    ///     dense_ascii: false,
+    /// };
+    /// let mut trie = DataTrie::with_layout(layout);
+    ///
+    /// trie.insert("word", 1);
+    /// assert!(trie.contains("word"));
+    /// assert_eq!(layout, trie.layout());
+    /// ```
+    pub fn with_layout(layout: StorageLayout) -> Self {
         DataTrie {
             root: TrieDataNode::new(),
             len: 0,
+            layout,
+            limits: TrieLimits::default(),
+            node_count: 0,
         }
     }
 
+    /// Returns a new instance of the trie whose insertions are bounded by 'limits'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError, TrieLimits};
+    /// let limits = TrieLimits {
+    ///     max_data_per_word: Some(2),
+    ///     ..Default::default()
+    /// };
+    /// let mut trie = DataTrie::with_limits(limits);
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word", 1));
+    /// assert_eq!(Ok(()), trie.try_insert("word", 2));
+    /// assert_eq!(Err(TrieError::TooManyData), trie.try_insert("word", 3));
+    /// assert_eq!(limits, trie.limits());
+    /// ```
+    pub fn with_limits(limits: TrieLimits) -> Self {
+        let mut trie = Self::new();
+        trie.limits = limits;
+        trie
+    }
+
+    /// Returns the layout the trie stores the children of its nodes with.
+    pub fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
+    /// Returns the limits the insertions into the trie are bounded by.
+    pub fn limits(&self) -> TrieLimits {
+        self.limits
+    }
+
+    /// Sets the limits for the following insertions into the trie.
+    /// Words and data that are already in the trie are kept, even if they exceed the limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError, TrieLimits};
+    /// let mut trie = DataTrie::new();
+    /// trie.insert("word", 1);
+    ///
+    /// trie.set_limits(TrieLimits {
+    ///     max_words: Some(1),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word", 2));
+    /// assert_eq!(Err(TrieError::TooManyWords), trie.try_insert("other", 3));
+    /// ```
+    pub fn set_limits(&mut self, limits: TrieLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the number of nodes in the trie, not counting the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("word", 1);
+    /// trie.insert("words", 2);
+    /// assert_eq!(5, trie.node_count());
+    /// ```
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
     /// Insert a word into the trie, with the corresponding data.
+    /// Tries with limits should insert with 'try_insert' instead. Here, a word
+    /// or data that exceeds the limits isn't inserted, and debug builds panic.
     ///
     /// # Examples
     ///
@@ -44,29 +198,71 @@ impl<D> DataTrie<D> {
     /// assert_eq!(vec![String::from("word1")], trie.get_all());
     /// ```
     pub fn insert(&mut self, word: &str, associated_data: D) {
+        let inserted = self.try_insert(word, associated_data);
+        debug_assert!(inserted.is_ok(), "the word exceeds the limits of the trie");
+    }
+
+    /// Insert a word into the trie, with the corresponding data, unless
+    /// that exceeds the limits of the trie. Before anything is inserted,
+    /// the number of characters of the word is checked, along with the number
+    /// of nodes, the memory they take, the number of words and the number
+    /// of data items of the word after the insertion.
+    /// The error names the exceeded limit, and nothing is inserted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError, TrieLimits};
+    /// let mut trie = DataTrie::with_limits(TrieLimits {
+    ///     max_nodes: Some(4),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word", "somedata"));
+    /// assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("words", "somedata2"));
+    /// assert_eq!(vec![String::from("word")], trie.get_all());
+    /// ```
+    pub fn try_insert(&mut self, word: &str, associated_data: D) -> Result<(), TrieError> {
+        self.check_limits(word, 1)?;
+
+        // The word is counted on its path while walking it, and uncounted
+        // again in the rarer case of it already being in the trie.
         let mut current = &mut self.root;
+        current.increment_subtree_words();
 
         for character in get_characters(word) {
             if current.children.get_mut(character).is_none() {
-                current.children.insert_new(character);
+                current.children.insert_new(character, self.layout);
+                self.node_count += 1;
             }
 
             current = current.children.get_mut(character).unwrap();
+            current.increment_subtree_words();
         }
 
-        if !current.is_associated() {
+        let is_new_word = !current.is_associated();
+
+        if is_new_word {
             self.len += 1;
             current.associate();
         }
 
         current.push_data(associated_data);
+
+        if !is_new_word {
+            self.root.subtract_path_words(get_characters(word), 1);
+        }
+
+        Ok(())
     }
 
     /// Insert a word into the trie, with no corresponding data.
     /// This function is very different from inserting a word into
     /// a regular trie, since it enables later attachment of data
     /// onto the inserted word. Type of trie must be annotated if
-    /// this is the first function call.
+    /// this is the first function call. Tries with limits should insert
+    /// with 'try_insert_no_data' instead. Here, a word that exceeds
+    /// the limits isn't inserted, and debug builds panic.
     ///
     /// # Examples
     ///
@@ -81,20 +277,57 @@ impl<D> DataTrie<D> {
     /// assert_eq!(vec![&"somedata"], trie.get_data("word1", false).unwrap());
     /// ```
     pub fn insert_no_data(&mut self, word: &str) {
+        let inserted = self.try_insert_no_data(word);
+        debug_assert!(inserted.is_ok(), "the word exceeds the limits of the trie");
+    }
+
+    /// Insert a word into the trie, with no corresponding data, unless
+    /// that exceeds the limits of the trie. The same limits as in 'try_insert'
+    /// are checked, except the number of data items, since none are added.
+    /// The error names the exceeded limit, and nothing is inserted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError, TrieLimits};
+    /// let mut trie = DataTrie::<&str>::with_limits(TrieLimits {
+    ///     max_word_len: Some(4),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert_no_data("word"));
+    /// assert_eq!(Err(TrieError::WordTooLong), trie.try_insert_no_data("words"));
+    /// ```
+    pub fn try_insert_no_data(&mut self, word: &str) -> Result<(), TrieError> {
+        self.check_limits(word, 0)?;
+
+        // The word is counted on its path while walking it, and uncounted
+        // again in the rarer case of it already being in the trie.
         let mut current = &mut self.root;
+        current.increment_subtree_words();
 
         for character in get_characters(word) {
             if current.children.get_mut(character).is_none() {
-                current.children.insert_new(character);
+                current.children.insert_new(character, self.layout);
+                self.node_count += 1;
             }
 
             current = current.children.get_mut(character).unwrap();
+            current.increment_subtree_words();
         }
 
-        if !current.is_associated() {
+        let is_new_word = !current.is_associated();
+
+        if is_new_word {
             self.len += 1;
             current.associate();
         }
+
+        if !is_new_word {
+            self.root.subtract_path_words(get_characters(word), 1);
+        }
+
+        Ok(())
     }
 
     /// Removes a word from the trie and returns data associated with that word.
@@ -120,24 +353,49 @@ impl<D> DataTrie<D> {
     /// assert_eq!(vec!["somedata2"], removed_data2.unwrap());
     /// ```
     pub fn remove(&mut self, word: &str) -> Option<Vec<D>> {
-        let current = self.get_final_node_mut(word)?;
-
-        if !current.children.is_empty() {
-            return current.clear_word_end_association(false).map(|data_vec| {
-                self.len -= 1;
-                data_vec.into_iter().collect()
-            });
+        if !self.get_final_node(word)?.is_associated() {
+            return None;
         }
 
-        let characters = get_characters(word);
+        self.len -= 1;
 
-        self.root
-            .remove_one_word(characters.into_iter())
-            .data
-            .map_or(Some(Vec::new()), |data_vec| {
-                self.len -= 1;
-                Some(data_vec.into_iter().collect())
-            })
+        let (data_vec, removed_nodes) =
+            self.root.remove_one_word(get_characters(word), self.layout);
+        self.node_count -= removed_nodes;
+
+        data_vec.map(|data_vec| data_vec.into_iter().collect())
+    }
+
+    /// Removes a word from the trie and returns data associated with that word,
+    /// reporting why the word couldn't be removed.
+    /// If no word begins with 'word', TrieError::NotFound is returned.
+    /// If the word is only a prefix to some word, TrieError::NotAWord is returned
+    /// and nothing is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError};
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("wording", "somedata");
+    ///
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_remove("word"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_remove("other"));
+    /// assert_eq!(Ok(vec!["somedata"]), trie.try_remove("wording"));
+    /// ```
+    pub fn try_remove(&mut self, word: &str) -> Result<Vec<D>, TrieError> {
+        self.get_word_node(word)?;
+
+        self.len -= 1;
+
+        let (data_vec, removed_nodes) =
+            self.root.remove_one_word(get_characters(word), self.layout);
+        self.node_count -= removed_nodes;
+
+        Ok(data_vec
+            .map(|data_vec| data_vec.into_iter().collect())
+            .unwrap_or_default())
     }
 
     /// Removes every word that begins with 'prefix' and collects all removed data.
@@ -167,20 +425,13 @@ impl<D> DataTrie<D> {
 
         let mut data_vec = Vec::new();
 
-        // Sum must be applied to the node's children and not to the node
-        // itself because the recursive function must disassociate a node
-        // to put its data in the vector. The optimization of adding one
-        // to the count when the node in question isn't root can't be used
-        // since the original node would've been already disassociated therefore
-        // not accounted for in self.len.
-        let word_count = current
-            .children
-            .values_mut()
-            .map(|child| child.remove_all_words_collect(&mut data_vec))
-            .sum::<usize>();
-        current.clear_children();
+        let removed_nodes = count_nodes(current);
+        let word_count = current.remove_descendants_collect(&mut data_vec);
 
+        self.root
+            .subtract_path_words(get_characters(prefix), word_count);
         self.len -= word_count;
+        self.node_count -= removed_nodes;
 
         Some(data_vec)
     }
@@ -222,6 +473,40 @@ impl<D> DataTrie<D> {
         }
     }
 
+    /// Returns a vector of references to data of some word that equals 'query'
+    /// or references to all found data of some word prefix when 'soft_match' is set to true.
+    /// Unlike 'get_data', a word without data is told apart from a missing word:
+    /// the word without data returns an empty vector, while TrieError::NotFound
+    /// is returned if no word begins with 'query', and TrieError::NotAWord if
+    /// 'soft_match' is set to false and 'query' is only a prefix to some word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError};
+    /// let mut trie = DataTrie::<&str>::new();
+    ///
+    /// trie.insert_no_data("word");
+    /// trie.insert("wording", "somedata");
+    ///
+    /// assert_eq!(Ok(vec![]), trie.try_get_data("word", false));
+    /// assert_eq!(Ok(vec![&"somedata"]), trie.try_get_data("word", true));
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_get_data("wordi", false));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_get_data("other", true));
+    /// ```
+    pub fn try_get_data(&self, query: &str, soft_match: bool) -> Result<Vec<&D>, TrieError> {
+        if soft_match {
+            return self.get_data(query, true).ok_or(TrieError::NotFound);
+        }
+
+        Ok(self
+            .get_word_node(query)?
+            .get_association()
+            .iter()
+            .flat_map(|data_vec| data_vec.iter())
+            .collect())
+    }
+
     /// Returns a vector of mutable references to data of some word that equals 'query'
     /// or mutable references to all found data of words that begin with 'query'
     /// when 'soft_match' is set to true.
@@ -292,6 +577,166 @@ impl<D> DataTrie<D> {
             .map(|data_vec| data_vec.into_iter().collect())
     }
 
+    /// Clears and returns data of some word, reporting why the data couldn't be cleared.
+    /// If no word begins with 'word', TrieError::NotFound is returned.
+    /// If the word is only a prefix to some word, TrieError::NotAWord is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError};
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("wording", "data1");
+    /// trie.insert("wording", "data2");
+    ///
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_clear_data("word"));
+    /// assert_eq!(Ok(vec!["data1", "data2"]), trie.try_clear_data("wording"));
+    /// assert_eq!(Ok(vec![]), trie.try_clear_data("wording"));
+    /// ```
+    pub fn try_clear_data(&mut self, word: &str) -> Result<Vec<D>, TrieError> {
+        Ok(self
+            .get_word_node_mut(word)?
+            .clear_word_end_association(true)
+            .map(|data_vec| data_vec.into_iter().collect())
+            .unwrap_or_default())
+    }
+
+    /// Removes and returns the data of some word for which 'predicate' returns true.
+    /// If the word is not found, None is returned.
+    /// When 'prune_empty' is set to true and the word is left without data,
+    /// the word is removed from the trie as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("word", 1);
+    /// trie.insert("word", 2);
+    /// trie.insert("word", 3);
+    ///
+    /// let removed_data = trie.remove_data_where("word", |&data| data % 2 == 1, true);
+    /// assert_eq!(vec![1, 3], removed_data.unwrap());
+    /// assert_eq!(vec![&2], trie.get_data("word", false).unwrap());
+    ///
+    /// trie.remove_data_where("word", |_| true, true);
+    /// assert!(!trie.contains("word"));
+    /// ```
+    pub fn remove_data_where(
+        &mut self,
+        word: &str,
+        predicate: impl FnMut(&D) -> bool,
+        prune_empty: bool,
+    ) -> Option<Vec<D>> {
+        let current = self.get_final_node_mut(word)?;
+        let removed_data = current.remove_data_where(predicate)?;
+
+        let is_emptied = !removed_data.is_empty()
+            && current
+                .get_association()
+                .as_ref()
+                .is_some_and(|data_vec| data_vec.is_empty());
+
+        if prune_empty && is_emptied {
+            self.remove(word);
+        }
+
+        Some(removed_data)
+    }
+
+    /// Keeps only the data for which 'f' returns true, given the word the data
+    /// belongs to, across the whole trie. When 'prune_empty' is set to true,
+    /// words left without data are removed from the trie. Words that had no
+    /// data to begin with are always kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("apple", 1);
+    /// trie.insert("apple", 20);
+    /// trie.insert("apricot", 3);
+    /// trie.insert("banana", 40);
+    ///
+    /// trie.retain(|word, data| {
+    ///     *data += 1;
+    ///     word.starts_with("ap") && *data > 10
+    /// }, true);
+    ///
+    /// assert_eq!(vec![String::from("apple")], trie.get_all());
+    /// assert_eq!(vec![&21], trie.get_data("apple", false).unwrap());
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&str, &mut D) -> bool, prune_empty: bool) {
+        let mut emptied_words = Vec::new();
+        self.root
+            .retain_data(&mut String::new(), &mut f, &mut emptied_words);
+
+        if prune_empty {
+            for word in emptied_words {
+                self.remove(&word);
+            }
+        }
+    }
+
+    /// Consumes the trie and returns a trie with the same words, where every
+    /// piece of data is transformed by 'f' given the word it belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("apple", "1");
+    /// trie.insert("apple", "2");
+    /// trie.insert_no_data("banana");
+    ///
+    /// let mapped_trie = trie.map_data(|word, data| format!("{word}-{data}"));
+    ///
+    /// assert_eq!(2, mapped_trie.len());
+    /// assert_eq!(
+    ///     vec![&String::from("apple-1"), &String::from("apple-2")],
+    ///     mapped_trie.get_data("apple", false).unwrap()
+    /// );
+    /// ```
+    pub fn map_data<E>(self, mut f: impl FnMut(&str, D) -> E) -> DataTrie<E> {
+        self.filter_map_data(|word, data| Some(f(word, data)))
+    }
+
+    /// Consumes the trie and returns a trie with the same words, where every
+    /// piece of data is transformed by 'f' given the word it belongs to.
+    /// Data for which 'f' returns None is dropped, but its word is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("word1", "1");
+    /// trie.insert("word1", "x");
+    /// trie.insert("word2", "y");
+    ///
+    /// let parsed_trie = trie.filter_map_data(|_, data| data.parse::<u32>().ok());
+    ///
+    /// assert_eq!(2, parsed_trie.len());
+    /// assert_eq!(vec![&1], parsed_trie.get_data("word1", false).unwrap());
+    /// assert_eq!(Vec::<&u32>::new(), parsed_trie.get_data("word2", false).unwrap());
+    /// ```
+    pub fn filter_map_data<E>(self, mut f: impl FnMut(&str, D) -> Option<E>) -> DataTrie<E> {
+        DataTrie {
+            root: self.root.filter_map_data(&mut String::new(), &mut f),
+            len: self.len,
+            layout: self.layout,
+            limits: self.limits,
+            node_count: self.node_count,
+        }
+    }
+
     /// Returns an option enum with a vector of owned strings
     /// representing all found words that begin with 'query'.
     /// If the word 'query' doesn't exist, None is returned.
@@ -326,11 +771,225 @@ impl<D> DataTrie<D> {
         }
 
         let mut words_vec = Vec::new();
-        current_node.find_words(&mut substring, &mut words_vec);
+        find_words(current_node, &mut substring, &mut words_vec);
 
         Some(words_vec)
     }
 
+    /// Returns an iterator over the words that fall within 'range' and
+    /// their data, in lexicographic order. The iterator walks straight
+    /// to the lower bound and stops at the upper bound, so only the words
+    /// that are returned are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut data_trie = DataTrie::new();
+    ///
+    /// data_trie.insert("lemon", 1);
+    /// data_trie.insert("mango", 2);
+    /// data_trie.insert("melon", 3);
+    /// data_trie.insert("nectarine", 4);
+    ///
+    /// let found_words = data_trie.range("m"..="nectarine").collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     vec![
+    ///         (String::from("mango"), &[2][..]),
+    ///         (String::from("melon"), &[3][..]),
+    ///         (String::from("nectarine"), &[4][..]),
+    ///     ],
+    ///     found_words
+    /// );
+    /// ```
+    pub fn range<'b, R: RangeBounds<&'b str>>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = (String, &[D])> + '_ {
+        WordRange::new(
+            &self.root,
+            range.start_bound().map(|word| *word),
+            range.end_bound().map(|word| *word),
+        )
+        .map(|(word, node)| (word, node.get_association().as_deref().unwrap_or_default()))
+    }
+
+    /// Returns an iterator over the words that are equal to or come after
+    /// 'word' and their data, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut data_trie = DataTrie::new();
+    ///
+    /// data_trie.insert("kite", 1);
+    /// data_trie.insert("kiwi", 2);
+    /// data_trie.insert("lime", 3);
+    ///
+    /// let found_words = data_trie.range_from("kiu").take(1).collect::<Vec<_>>();
+    /// assert_eq!(vec![(String::from("kiwi"), &[2][..])], found_words);
+    /// ```
+    pub fn range_from(&self, word: &str) -> impl Iterator<Item = (String, &[D])> + '_ {
+        WordRange::new(&self.root, Bound::Included(word), Bound::Unbounded)
+            .map(|(word, node)| (word, node.get_association().as_deref().unwrap_or_default()))
+    }
+
+    /// Returns the position of 'word' in the lexicographic order of all words
+    /// in the trie, which is the number of words that come before it.
+    /// If the word is not found, None is returned.
+    /// Every node caches the number of words below it,
+    /// so only the path to the word is walked.
+    /// Requires the 'subtree-counts' feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("cherry", 1);
+    /// trie.insert("apple", 2);
+    /// trie.insert("banana", 3);
+    ///
+    /// assert_eq!(Some(0), trie.rank("apple"));
+    /// assert_eq!(Some(2), trie.rank("cherry"));
+    /// assert_eq!(None, trie.rank("banan"));
+    /// ```
+    #[cfg(feature = "subtree-counts")]
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        rank(&self.root, word)
+    }
+
+    /// Returns the word at position 'n' (counting from 0) in the
+    /// lexicographic order of all words in the trie.
+    /// If the trie has 'n' words or fewer, None is returned.
+    /// Every node caches the number of words below it,
+    /// so only the path to the found word is walked.
+    /// Requires the 'subtree-counts' feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("cherry", 1);
+    /// trie.insert("apple", 2);
+    /// trie.insert("banana", 3);
+    ///
+    /// assert_eq!(Some(String::from("banana")), trie.select(1));
+    /// assert_eq!(None, trie.select(3));
+    /// ```
+    #[cfg(feature = "subtree-counts")]
+    pub fn select(&self, n: usize) -> Option<String> {
+        select(&self.root, n)
+    }
+
+    /// Returns statistics on the structure of the trie and an estimate
+    /// of its heap memory usage. Every node is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("eat", 1);
+    /// trie.insert("eats", 2);
+    /// trie.insert("tea", 3);
+    ///
+    /// let stats = trie.stats();
+    /// assert_eq!(8, stats.node_count);
+    /// assert_eq!(3, stats.word_count);
+    /// assert_eq!(4, stats.max_depth);
+    /// assert_eq!(vec![2, 5, 1], stats.branching_histogram);
+    /// ```
+    pub fn stats(&self) -> TrieStats {
+        TrieStats::collect(&self.root)
+    }
+
+    /// Returns the description of the trie in the Graphviz DOT language.
+    /// Nodes that words end on are drawn as double circles and edges are
+    /// labeled with their characters, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("ab", 1);
+    /// trie.insert("ac", 2);
+    ///
+    /// let dot = trie.to_dot();
+    /// assert!(dot.starts_with("digraph trie {"));
+    /// assert!(dot.contains("n1 -> n2 [label=\"b\"];"));
+    /// assert!(dot.contains("n2 [shape=doublecircle, label=\"\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        render::to_dot(&self.root, |_| None)
+    }
+
+    /// Returns the description of the trie in the Graphviz DOT language,
+    /// like `to_dot`, with the data of every word shown in its node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("ab", 1);
+    /// trie.insert("ab", 2);
+    ///
+    /// let dot = trie.to_dot_with_data();
+    /// assert!(dot.contains("n2 [shape=doublecircle, label=\"1, 2\"];"));
+    /// ```
+    pub fn to_dot_with_data(&self) -> String
+    where
+        D: Debug,
+    {
+        render::to_dot(&self.root, |node| {
+            node.get_association().as_ref().map(|data_vec| {
+                data_vec
+                    .iter()
+                    .map(|data| format!("{data:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        })
+    }
+
+    /// Returns an indented drawing of the trie for debugging, one node per line,
+    /// that can be printed or formatted into a string. Children are ordered
+    /// by their character and word ends are marked with '*'. Nodes deeper than
+    /// 'max_depth' characters aren't drawn and drawing stops after 'max_nodes'
+    /// nodes; every skipped part is marked with '...'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut trie = DataTrie::new();
+    ///
+    /// trie.insert("ab", 1);
+    /// trie.insert("ac", 2);
+    ///
+    /// let drawing = trie.display_tree(None, None).to_string();
+    /// assert_eq!("(root)\n└── a\n    ├── b *\n    └── c *\n", drawing);
+    ///
+    /// let drawing = trie.display_tree(Some(1), None).to_string();
+    /// assert_eq!("(root)\n└── a\n    └── ...\n", drawing);
+    /// ```
+    pub fn display_tree(
+        &self,
+        max_depth: Option<usize>,
+        max_nodes: Option<usize>,
+    ) -> impl fmt::Display + '_ {
+        TreeDisplay::new(&self.root, max_depth, max_nodes)
+    }
+
     /// Returns the vector of longest words found in the trie.
     ///
     /// # Examples
@@ -349,19 +1008,7 @@ impl<D> DataTrie<D> {
     /// assert_eq!(longest_words, found_words);
     /// ```
     pub fn get_longest(&self) -> Vec<String> {
-        let mut words = Vec::new();
-        let mut collector = String::new();
-        let mut best_len = None;
-        self.root.words_min_max(
-            &mut collector,
-            0,
-            &mut words,
-            &mut best_len,
-            #[cfg(feature = "unicode")]
-            true,
-            Ordering::Greater,
-        );
-        words
+        words_min_max(&self.root, Ordering::Greater)
     }
 
     /// Returns the vector of shortest words found in the trie.
@@ -382,19 +1029,7 @@ impl<D> DataTrie<D> {
     /// assert_eq!(shortest_word, found_words);
     /// ```
     pub fn get_shortest(&self) -> Vec<String> {
-        let mut words = Vec::new();
-        let mut collector = String::new();
-        let mut best_len = None;
-        self.root.words_min_max(
-            &mut collector,
-            0,
-            &mut words,
-            &mut best_len,
-            #[cfg(feature = "unicode")]
-            true,
-            Ordering::Less,
-        );
-        words
+        words_min_max(&self.root, Ordering::Less)
     }
 
     /// Returns the number of words in the trie.
@@ -423,6 +1058,8 @@ impl<D> DataTrie<D> {
 
     /// Returns the number of words that start with 'prefix'.
     /// If the sequence 'prefix' is not found, None is returned.
+    /// With the 'subtree-counts' feature, the number is cached in the
+    /// prefix's node, so only the path to it is walked.
     ///
     /// # Examples
     /// ```
@@ -439,7 +1076,7 @@ impl<D> DataTrie<D> {
     pub fn len_prefix(&self, prefix: &str) -> usize {
         match self.get_final_node(prefix) {
             None => 0,
-            Some(node) => node.count_words() - node.is_associated() as usize,
+            Some(node) => node.subtree_words() - node.is_associated() as usize,
         }
     }
 
@@ -525,8 +1162,35 @@ impl<D> DataTrie<D> {
     /// assert_eq!(0, data_trie.len());
     /// ```
     pub fn clear(&mut self) {
-        self.root.clear_children();
+        self.root = TrieDataNode::new();
         self.len = 0;
+        self.node_count = 0;
+    }
+
+    /// Consumes the trie and returns an Aho-Corasick automaton for
+    /// finding occurrences of all its words inside some text,
+    /// along with the data of every found word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut data_trie = DataTrie::new();
+    ///
+    /// data_trie.insert("tri", 1);
+    /// data_trie.insert("trie", 2);
+    ///
+    /// let matcher = data_trie.into_matcher();
+    /// let found_data = matcher
+    ///     .find_overlapping("a trie")
+    ///     .iter()
+    ///     .flat_map(|m| m.data())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(vec![&1, &2], found_data);
+    /// ```
+    pub fn into_matcher(self) -> Matcher<D> {
+        Matcher::from_root(self.root, |node| node.into_parts())
     }
 
     /// Function for getting the last node in a character sequence.
@@ -540,6 +1204,52 @@ impl<D> DataTrie<D> {
         Some(current)
     }
 
+    /// Function checks that inserting a word with 'new_data' more data items
+    /// stays within the limits of the trie.
+    fn check_limits(&self, word: &str, new_data: usize) -> Result<(), TrieError> {
+        if self.limits.is_unlimited() {
+            return Ok(());
+        }
+
+        let (word_len, new_nodes, last) = insertion_path(&self.root, word);
+        let word_end = last.and_then(|node| node.get_association().as_ref());
+
+        self.limits.check(Insertion {
+            word_len,
+            node_bytes: size_of::<(char, TrieDataNode<D>)>(),
+            node_count: self.node_count,
+            new_nodes,
+            word_count: self.len,
+            new_words: word_end.is_none() as usize,
+            data_count: word_end.map_or(0, |data_vec| data_vec.len()),
+            new_data,
+        })
+    }
+
+    /// Function for getting the last node of a word, or the reason
+    /// the character sequence isn't a word in the trie.
+    fn get_word_node(&self, word: &str) -> Result<&TrieDataNode<D>, TrieError> {
+        let node = self.get_final_node(word).ok_or(TrieError::NotFound)?;
+
+        if node.is_associated() {
+            Ok(node)
+        } else {
+            Err(TrieError::NotAWord)
+        }
+    }
+
+    /// Function for getting the last node of a word (mutable), or the reason
+    /// the character sequence isn't a word in the trie.
+    fn get_word_node_mut(&mut self, word: &str) -> Result<&mut TrieDataNode<D>, TrieError> {
+        let node = self.get_final_node_mut(word).ok_or(TrieError::NotFound)?;
+
+        if node.is_associated() {
+            Ok(node)
+        } else {
+            Err(TrieError::NotAWord)
+        }
+    }
+
     /// Function for getting the last node in a character sequence (mutable).
     fn get_final_node_mut(&mut self, query: &str) -> Option<&mut TrieDataNode<D>> {
         let mut current = &mut self.root;
@@ -552,11 +1262,133 @@ impl<D> DataTrie<D> {
     }
 }
 
+#[cfg(feature = "regex")]
+impl<D> DataTrie<D> {
+    /// Returns all words in the trie that match the regular expression 'pattern'
+    /// as a whole, as if it were surrounded by '^' and '$', along with their data.
+    /// The pattern is compiled into a DFA that runs while the trie is walked,
+    /// so subtrees that no word matching the pattern can be in are never visited.
+    /// If the pattern can't be compiled, the boxed error is returned.
+    /// Order is not guaranteed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let mut data_trie = DataTrie::new();
+    ///
+    /// data_trie.insert("unreadable", 1);
+    /// data_trie.insert("reusable", 2);
+    /// data_trie.insert("readable", 3);
+    /// data_trie.insert("unable", 4);
+    ///
+    /// let found_words = data_trie.find_regex("^un[a-z]+able$").unwrap();
+    /// assert_eq!(vec![(String::from("unreadable"), &[1][..])], found_words);
+    /// ```
+    pub fn find_regex(
+        &self,
+        pattern: &str,
+    ) -> Result<Vec<(String, &[D])>, Box<regex_automata::dfa::dense::BuildError>> {
+        let mut found_words = Vec::new();
+        find_matching(&self.root, pattern, |node, word| {
+            let data_vec = node.get_association().as_ref().unwrap();
+            found_words.push((String::from(word), data_vec.as_slice()));
+        })?;
+
+        Ok(found_words)
+    }
+}
+
+#[cfg(feature = "fst")]
+impl<D> DataTrie<D> {
+    /// Returns an `fst::Map` mapping every word to its data item,
+    /// turned into a value by 'to_value'. The finite-state transducer format
+    /// holds exactly one integer per key, so a word with no data or more than one
+    /// data item returns an error, unless 'lossy' is set. With 'lossy', such words
+    /// are left out and any data after the first item is ignored.
+    /// Words are visited in lexicographic order, which is the order the format requires.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{DataTrie, TrieError};
+    /// let mut data_trie = DataTrie::<u32>::new();
+    ///
+    /// data_trie.insert("word1", 1);
+    /// data_trie.insert("word2", 2);
+    ///
+    /// let map = data_trie.to_fst_map(|&data| u64::from(data), false).unwrap();
+    /// assert_eq!(Some(2), map.get("word2"));
+    ///
+    /// data_trie.insert_no_data("word3");
+    /// assert_eq!(
+    ///     Err(TrieError::DataCountMismatch),
+    ///     data_trie.to_fst_map(|&data| u64::from(data), false).map(|map| map.len())
+    /// );
+    ///
+    /// let map = data_trie.to_fst_map(|&data| u64::from(data), true).unwrap();
+    /// assert_eq!(None, map.get("word3"));
+    /// ```
+    pub fn to_fst_map(
+        &self,
+        to_value: impl Fn(&D) -> u64,
+        lossy: bool,
+    ) -> Result<fst::Map<Vec<u8>>, TrieError> {
+        let mut builder = fst::MapBuilder::memory();
+
+        for (word, data) in self.range(..) {
+            let first_data = match data {
+                [first_data] => first_data,
+                [first_data, ..] if lossy => first_data,
+                [] if lossy => continue,
+                _ => return Err(TrieError::DataCountMismatch),
+            };
+
+            builder
+                .insert(word, to_value(first_data))
+                .expect("words are visited in lexicographic order, each once");
+        }
+
+        Ok(builder.into_map())
+    }
+
+    /// Returns a new data trie where every key of an `fst::Map` is
+    /// inserted with its value, turned into data by 'to_data', as the only data item.
+    /// If some key isn't valid UTF-8, the error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::DataTrie;
+    /// let map = fst::Map::from_iter(vec![("word1", 1), ("word2", 2)]).unwrap();
+    ///
+    /// let data_trie = DataTrie::from_fst_map(&map, |value| value as u8).unwrap();
+    /// assert_eq!(vec![&2], data_trie.get_data("word2", false).unwrap());
+    ///
+    /// let data_trie = DataTrie::from_fst_map(&map, |value| format!("#{value}")).unwrap();
+    /// assert_eq!(vec![&String::from("#1")], data_trie.get_data("word1", false).unwrap());
+    /// ```
+    pub fn from_fst_map<T: AsRef<[u8]>>(
+        map: &fst::Map<T>,
+        to_data: impl Fn(u64) -> D,
+    ) -> Result<Self, Utf8Error> {
+        let mut data_trie = DataTrie::new();
+        let mut stream = map.stream();
+
+        while let Some((key, value)) = stream.next() {
+            data_trie.insert(core::str::from_utf8(key)?, to_data(value));
+        }
+
+        Ok(data_trie)
+    }
+}
+
 impl<D> ops::Add for DataTrie<D> {
     type Output = DataTrie<D>;
 
     /// Operation + merges two tries, leaving out duplicate words.
-    /// The smaller trie is always added to the larger one for efficiency.
+    /// The smaller trie is always added to the larger one for efficiency,
+    /// and the result keeps the storage layout of the larger one.
     ///
     /// # Examples
     ///
@@ -588,10 +1420,11 @@ impl<D> ops::Add for DataTrie<D> {
             (rhs, self)
         };
 
-        bigger.root += smaller.root;
+        let merged_nodes = bigger.root.merge(smaller.root, bigger.layout);
+        bigger.node_count += smaller.node_count - merged_nodes;
 
         // Number of words needs to be recalculated.
-        bigger.len = bigger.root.count_words();
+        bigger.len = bigger.root.subtree_words();
 
         bigger
     }
@@ -599,6 +1432,7 @@ impl<D> ops::Add for DataTrie<D> {
 
 impl<D> ops::AddAssign for DataTrie<D> {
     /// Operation += merges two tries, leaving out duplicate words.
+    /// The limits of the left trie are kept, but aren't enforced on the merged words.
     ///
     /// # Examples
     ///
@@ -624,10 +1458,11 @@ impl<D> ops::AddAssign for DataTrie<D> {
     /// assert_eq!(data_trie_1, correct);
     /// ```
     fn add_assign(&mut self, rhs: Self) {
-        self.root += rhs.root;
+        let merged_nodes = self.root.merge(rhs.root, self.layout);
+        self.node_count += rhs.node_count - merged_nodes;
 
         // Number of words needs to be recalculated.
-        self.len = self.root.count_words();
+        self.len = self.root.subtree_words();
     }
 }
 
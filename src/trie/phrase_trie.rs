@@ -0,0 +1,650 @@
+use crate::Match;
+use crate::StorageLayout;
+use crate::TrieError;
+use crate::trie_node::{TrieDataNode, find_words};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Hook that splits text into tokens, returned with the byte offsets
+/// they start at, in the order they appear in the text.
+pub type Tokenizer = fn(&str) -> Vec<(usize, &str)>;
+
+/// Data trie whose edges are whole tokens instead of characters, used for
+/// indexing multi-word phrases like "new york city" that are looked up
+/// one token at a time.
+///
+/// Tokens are interned: every distinct token is given a key when it's first
+/// inserted, and the nodes branch on those keys with the same child storage
+/// that the other tries use. Tokens are compared exactly as the tokenizer
+/// returns them. A token is freed once the last phrase with it is removed,
+/// and its key is given to the next new token.
+/// Phrases are returned as their tokens joined by single spaces.
+#[derive(Debug)]
+pub struct PhraseTrie<D> {
+    root: TrieDataNode<D>,
+    interner: TokenInterner,
+    tokenize: Tokenizer,
+    len: usize,
+    layout: StorageLayout,
+}
+
+impl<D> Default for PhraseTrie<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> PhraseTrie<D> {
+    /// Returns a new instance of the trie that splits text into tokens on word bounds.
+    /// With the 'unicode' feature, the word bounds of Unicode are used and tokens
+    /// without letters or digits are left out, otherwise tokens are the runs
+    /// of alphanumeric characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let trie = PhraseTrie::<()>::new();
+    ///
+    /// assert_eq!(vec![(0, "new"), (4, "york"), (10, "city")], trie.tokenize("new york, city!"));
+    /// ```
+    pub fn new() -> Self {
+        Self::with_tokenizer(word_tokens)
+    }
+
+    /// Returns a new instance of the trie that splits text into tokens with 'tokenize'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::with_tokenizer(|text| {
+    ///     text.split(',').scan(0, |start, token| {
+    ///         let offset = *start;
+    ///         *start += token.len() + 1;
+    ///         Some((offset, token))
+    ///     }).collect()
+    /// });
+    ///
+    /// trie.insert("a b,c", 1);
+    /// assert_eq!(vec![String::from("a b c")], trie.get_all());
+    /// ```
+    pub fn with_tokenizer(tokenize: Tokenizer) -> Self {
+        PhraseTrie {
+            root: TrieDataNode::new(),
+            interner: TokenInterner::default(),
+            tokenize,
+            len: 0,
+            layout: StorageLayout::default(),
+        }
+    }
+
+    /// Returns a new instance of the trie that splits text into tokens on word bounds,
+    /// and stores the children of its nodes according to 'layout'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{PhraseTrie, StorageLayout};
+    /// let layout = StorageLayout {
+    ///     promotion_threshold: 8,
+    ///     sorted_small: true,
+    ///     dense_ascii: false,
+    /// };
+    /// let mut trie = PhraseTrie::with_layout(layout);
+    ///
+    /// trie.insert("new york", 1);
+    /// assert!(trie.contains("new york"));
+    /// assert_eq!(layout, trie.layout());
+    /// ```
+    pub fn with_layout(layout: StorageLayout) -> Self {
+        PhraseTrie {
+            layout,
+            ..Self::new()
+        }
+    }
+
+    /// Returns the layout the trie stores the children of its nodes with.
+    pub fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
+    /// Returns the tokens of 'text' with the byte offsets they start at,
+    /// as the trie splits them.
+    pub fn tokenize<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+        (self.tokenize)(text)
+    }
+
+    /// Insert a phrase into the trie, with the corresponding data.
+    /// If the trie can't intern any more distinct tokens, nothing is inserted,
+    /// and debug builds panic. 'try_insert' reports the error instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york city", "NYC");
+    /// assert_eq!(vec![&"NYC"], trie.get_data("new  york city").unwrap());
+    /// ```
+    pub fn insert(&mut self, phrase: &str, associated_data: D) {
+        let inserted = self.try_insert(phrase, associated_data);
+        debug_assert!(
+            inserted.is_ok(),
+            "the trie can't intern the tokens of the phrase"
+        );
+    }
+
+    /// Insert a phrase into the trie, with the corresponding data, unless
+    /// the trie can't intern its tokens. A little over a million distinct
+    /// tokens can be interned, after which TrieError::CapacityExceeded
+    /// is returned for phrases with new tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("new york", 1));
+    /// assert!(trie.contains("new york"));
+    /// ```
+    pub fn try_insert(&mut self, phrase: &str, associated_data: D) -> Result<(), TrieError> {
+        let current = self.insert_path(phrase)?;
+        current.push_data(associated_data);
+
+        Ok(())
+    }
+
+    /// Insert a phrase into the trie, with no corresponding data.
+    /// Type of trie must be annotated if this is the first function call.
+    /// If the trie can't intern any more distinct tokens, nothing is inserted,
+    /// and debug builds panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::<&str>::new();
+    ///
+    /// trie.insert_no_data("new york");
+    /// assert_eq!(Vec::<&&str>::new(), trie.get_data("new york").unwrap());
+    /// ```
+    pub fn insert_no_data(&mut self, phrase: &str) {
+        let inserted = self.insert_path(phrase).is_ok();
+        debug_assert!(inserted, "the trie can't intern the tokens of the phrase");
+    }
+
+    /// Removes a phrase from the trie and returns data associated with that phrase.
+    /// Tokens that no other phrase has are freed. If the phrase is not found,
+    /// None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// trie.insert("new york city", 2);
+    ///
+    /// assert_eq!(Some(vec![1]), trie.remove("new york"));
+    /// assert_eq!(None, trie.remove("new york"));
+    /// assert_eq!(vec![String::from("new york city")], trie.get_all());
+    /// ```
+    pub fn remove(&mut self, phrase: &str) -> Option<Vec<D>> {
+        if !self.get_final_node(phrase)?.is_associated() {
+            return None;
+        }
+
+        let keys = self.keys(phrase)?;
+        let (data_vec, _) = self.root.remove_one_word(keys.iter().copied(), self.layout);
+        self.interner.release(&keys);
+        self.len -= 1;
+
+        data_vec.map(|data_vec| data_vec.into_iter().collect())
+    }
+
+    /// Returns a vector of references to the data of a phrase.
+    /// If the phrase is not found, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// trie.insert("new york", 2);
+    ///
+    /// assert_eq!(vec![&1, &2], trie.get_data("new york").unwrap());
+    /// assert_eq!(None, trie.get_data("new"));
+    /// ```
+    pub fn get_data(&self, phrase: &str) -> Option<Vec<&D>> {
+        self.get_final_node(phrase)?
+            .get_association()
+            .as_ref()
+            .map(|data_vec| data_vec.iter().collect())
+    }
+
+    /// Returns an option enum with a vector of owned strings representing
+    /// all found phrases that begin with the tokens of 'query'.
+    /// If no phrase begins with them, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york city", 1);
+    /// trie.insert("new jersey", 2);
+    /// trie.insert("newark", 3);
+    ///
+    /// let mut found_phrases = trie.get("new").unwrap();
+    /// found_phrases.sort();
+    /// assert_eq!(vec![String::from("new jersey"), String::from("new york city")], found_phrases);
+    /// ```
+    pub fn get(&self, query: &str) -> Option<Vec<String>> {
+        self.phrases_below(self.keys(query)?)
+    }
+
+    /// Returns a vector of owned strings representing all phrases in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// assert_eq!(vec![String::from("new york")], trie.get_all());
+    /// ```
+    pub fn get_all(&self) -> Vec<String> {
+        self.phrases_below(Vec::new()).unwrap()
+    }
+
+    /// Returns the longest known phrases found in 'text', from left to right.
+    /// Every phrase starts where the previous one ended or later, and at every
+    /// token the longest phrase that starts with it is taken. Matches span the text
+    /// from the start of their first token to the end of their last one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", "state");
+    /// trie.insert("new york city", "city");
+    /// trie.insert("city hall", "building");
+    ///
+    /// let found_phrases = trie
+    ///     .find_phrases_in("From New York: new york  city, then city hall.")
+    ///     .iter()
+    ///     .map(|m| (m.word(), m.data()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     vec![("new york  city", &["city"][..]), ("city hall", &["building"][..])],
+    ///     found_phrases
+    /// );
+    /// ```
+    pub fn find_phrases_in<'a>(&'a self, text: &'a str) -> Vec<Match<'a, D>> {
+        let tokens = self.tokenize(text);
+        let keys = tokens
+            .iter()
+            .map(|(_, token)| self.interner.get(token))
+            .collect::<Vec<_>>();
+
+        let mut found_matches = Vec::new();
+        let mut first = 0;
+
+        while first < tokens.len() {
+            let mut current = &self.root;
+            let mut longest = None;
+
+            for (last, key) in keys.iter().enumerate().skip(first) {
+                let Some(next) = key.and_then(|key| current.children.get(key)) else {
+                    break;
+                };
+
+                current = next;
+                if let Some(data_vec) = current.get_association() {
+                    longest = Some((last, data_vec));
+                }
+            }
+
+            let Some((last, data_vec)) = longest else {
+                first += 1;
+                continue;
+            };
+
+            let start = tokens[first].0;
+            let end = tokens[last].0 + tokens[last].1.len();
+            found_matches.push(Match::new(start, end, &text[start..end], data_vec));
+
+            first = last + 1;
+        }
+
+        found_matches
+    }
+
+    /// Returns the number of phrases in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// trie.insert("new york", 2);
+    /// assert_eq!(1, trie.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of distinct tokens of the phrases in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// trie.insert("york city", 2);
+    /// assert_eq!(3, trie.token_count());
+    ///
+    /// trie.remove("york city");
+    /// assert_eq!(2, trie.token_count());
+    /// ```
+    pub fn token_count(&self) -> usize {
+        self.interner.len()
+    }
+
+    /// Returns true if the trie contains 'query' as a phrase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york city", 1);
+    /// assert!(trie.contains("new york city"));
+    /// assert!(!trie.contains("new york"));
+    /// ```
+    pub fn contains(&self, query: &str) -> bool {
+        self.keys(query)
+            .is_some_and(|keys| self.contains_keys(&keys))
+    }
+
+    /// Returns true if no phrases are in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// trie.remove("new york");
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all phrases and interned tokens from the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::PhraseTrie;
+    /// let mut trie = PhraseTrie::new();
+    ///
+    /// trie.insert("new york", 1);
+    /// trie.clear();
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.root = TrieDataNode::new();
+        self.interner = TokenInterner::default();
+        self.len = 0;
+    }
+}
+
+impl<D> PhraseTrie<D> {
+    /// Function returns the keys of the tokens of a phrase,
+    /// or None if one of the tokens was never interned.
+    fn keys(&self, phrase: &str) -> Option<Vec<char>> {
+        self.tokenize(phrase)
+            .into_iter()
+            .map(|(_, token)| self.interner.get(token))
+            .collect()
+    }
+
+    /// Function returns every phrase that begins with a path of keys.
+    fn phrases_below(&self, keys: Vec<char>) -> Option<Vec<String>> {
+        let current = self.get_key_node(&keys)?;
+        let mut substring = keys.into_iter().collect::<String>();
+
+        let mut found_keys = Vec::new();
+        find_words(current, &mut substring, &mut found_keys);
+
+        Some(
+            found_keys
+                .iter()
+                .map(|keys| {
+                    keys.chars()
+                        .map(|key| self.interner.resolve(key))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect(),
+        )
+    }
+
+    /// Function for getting the last node of a phrase.
+    fn get_final_node(&self, phrase: &str) -> Option<&TrieDataNode<D>> {
+        self.get_key_node(&self.keys(phrase)?)
+    }
+
+    /// Function for getting the last node of a path of keys.
+    fn get_key_node(&self, keys: &[char]) -> Option<&TrieDataNode<D>> {
+        let mut current = &self.root;
+
+        for &key in keys {
+            current = current.children.get(key)?;
+        }
+
+        Some(current)
+    }
+
+    /// Function returns true if a path of keys ends with a phrase.
+    fn contains_keys(&self, keys: &[char]) -> bool {
+        self.get_key_node(keys)
+            .is_some_and(|node| node.is_associated())
+    }
+
+    /// Function interns the tokens of a phrase and marks the last node of
+    /// the phrase as an end of a phrase, inserting the nodes that are missing.
+    fn insert_path(&mut self, phrase: &str) -> Result<&mut TrieDataNode<D>, TrieError> {
+        let tokens = (self.tokenize)(phrase)
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect::<Vec<_>>();
+        let keys = self
+            .interner
+            .intern_all(&tokens)
+            .ok_or(TrieError::CapacityExceeded)?;
+
+        // Words and tokens are only counted on the path of a phrase that isn't in the trie yet.
+        let is_new = !self.contains_keys(&keys);
+        if is_new {
+            self.interner.retain(&keys);
+        }
+
+        let layout = self.layout;
+        let mut current = &mut self.root;
+        if is_new {
+            current.increment_subtree_words();
+        }
+
+        for key in keys {
+            if current.children.get_mut(key).is_none() {
+                current.children.insert_new(key, layout);
+            }
+
+            current = current.children.get_mut(key).unwrap();
+            if is_new {
+                current.increment_subtree_words();
+            }
+        }
+
+        if is_new {
+            current.associate();
+            self.len += 1;
+        }
+
+        Ok(current)
+    }
+}
+
+/// Distinct tokens of a phrase trie, each given a character as its key.
+/// Keys are handed out in order, skipping the surrogate code points,
+/// which aren't characters. Every token counts the tokens of the phrases
+/// in the trie that it stands for, and is freed when the count drops to zero,
+/// leaving its key to be reused.
+#[derive(Debug, Default)]
+struct TokenInterner {
+    keys: BTreeMap<String, (char, usize)>,
+    tokens: Vec<Option<String>>,
+    free_ids: Vec<u32>,
+}
+
+impl TokenInterner {
+    /// Number of keys that can be handed out, one for every character.
+    const KEY_COUNT: usize = char::MAX as usize + 1 - 0x800;
+
+    /// Function returns the key of a token, if the token was interned.
+    fn get(&self, token: &str) -> Option<char> {
+        self.keys.get(token).map(|&(key, _)| key)
+    }
+
+    /// Function returns the keys of 'tokens', interning the new ones. Returns None,
+    /// without interning anything, if there aren't enough keys left for the new tokens.
+    /// New tokens aren't counted until they're retained.
+    fn intern_all(&mut self, tokens: &[&str]) -> Option<Vec<char>> {
+        let mut new_tokens = tokens
+            .iter()
+            .filter(|token| !self.keys.contains_key(**token))
+            .collect::<Vec<_>>();
+        new_tokens.sort_unstable();
+        new_tokens.dedup();
+
+        let keys_left = self.free_ids.len() + (Self::KEY_COUNT - self.tokens.len());
+        if new_tokens.len() > keys_left {
+            return None;
+        }
+
+        Some(tokens.iter().map(|token| self.intern(token)).collect())
+    }
+
+    /// Function returns the key of a token, interning the token if it's new.
+    /// There must be a key left for it.
+    fn intern(&mut self, token: &str) -> char {
+        if let Some(key) = self.get(token) {
+            return key;
+        }
+
+        let id = self.free_ids.pop().unwrap_or_else(|| {
+            self.tokens.push(None);
+            (self.tokens.len() - 1) as u32
+        });
+        let key = char::from_u32(if id < 0xD800 { id } else { id + 0x800 }).unwrap();
+
+        self.keys.insert(token.to_string(), (key, 0));
+        self.tokens[id as usize] = Some(token.to_string());
+
+        key
+    }
+
+    /// Function counts one more use of every key in 'keys'.
+    fn retain(&mut self, keys: &[char]) {
+        for &key in keys {
+            let token = self.tokens[Self::id(key) as usize].as_deref().unwrap();
+            self.keys.get_mut(token).unwrap().1 += 1;
+        }
+    }
+
+    /// Function counts one less use of every key in 'keys',
+    /// freeing the tokens that aren't used anymore.
+    fn release(&mut self, keys: &[char]) {
+        for &key in keys {
+            let id = Self::id(key);
+            let token = self.tokens[id as usize].as_deref().unwrap();
+            let (_, count) = self.keys.get_mut(token).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                let token = self.tokens[id as usize].take().unwrap();
+                self.keys.remove(&token);
+                self.free_ids.push(id);
+            }
+        }
+    }
+
+    /// Function returns the token of a key.
+    fn resolve(&self, key: char) -> &str {
+        self.tokens[Self::id(key) as usize].as_deref().unwrap()
+    }
+
+    /// Function returns the position of a key among the keys that can be handed out.
+    fn id(key: char) -> u32 {
+        let id = key as u32;
+        if id < 0xD800 { id } else { id - 0x800 }
+    }
+
+    /// Function returns the number of interned tokens.
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+/// Function splits text on the word bounds of Unicode,
+/// leaving out the tokens without letters or digits.
+#[cfg(feature = "unicode")]
+pub(crate) fn word_tokens(text: &str) -> Vec<(usize, &str)> {
+    text.split_word_bound_indices()
+        .filter(|(_, token)| token.chars().any(char::is_alphanumeric))
+        .collect()
+}
+
+/// Without the 'unicode' feature, tokens are the runs of alphanumeric characters.
+#[cfg(not(feature = "unicode"))]
+pub(crate) fn word_tokens(text: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (position, character) in text.char_indices() {
+        if character.is_alphanumeric() {
+            start.get_or_insert(position);
+        } else if let Some(start) = start.take() {
+            tokens.push((start, &text[start..position]));
+        }
+    }
+
+    if let Some(start) = start {
+        tokens.push((start, &text[start..]));
+    }
+
+    tokens
+}
@@ -1,33 +1,194 @@
-use std::cmp::Ordering;
-use std::ops;
+#[cfg(feature = "regex")]
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops;
+use core::ops::{Bound, RangeBounds};
+#[cfg(feature = "fst")]
+use core::str::Utf8Error;
 
+#[cfg(feature = "fst")]
+use fst::Streamer;
+
+#[cfg(feature = "serde")]
+use crate::child_storage::relayout_tree;
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
 
+use crate::Adjacency;
+use crate::Matcher;
+use crate::StorageLayout;
+use crate::TrieError;
+use crate::TrieLimits;
+use crate::TrieStats;
+use crate::grid::find_in_grid;
+use crate::limits::{Insertion, insertion_path};
+#[cfg(feature = "regex")]
+use crate::regex::find_matching;
+use crate::render;
+use crate::render::TreeDisplay;
 use crate::trie::get_characters;
-use crate::trie_node::TrieDatalessNode;
+use crate::trie_node::{
+    TrieDatalessNode, TrieNode, WordRange, anagrams, count_nodes, find_words, words_min_max,
+};
+#[cfg(feature = "subtree-counts")]
+use crate::trie_node::{rank, select};
+use thin_vec::ThinVec;
 
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
-    serde(crate = "serde_crate")
+    serde(crate = "serde_crate", from = "TrieFields")
 )]
 #[derive(Debug, Default)]
 pub struct Trie {
     root: TrieDatalessNode,
     len: usize,
+    layout: StorageLayout,
+    limits: TrieLimits,
+    node_count: usize,
+}
+
+/// Fields of a deserialized trie, whose children are laid out
+/// again with the layout of the trie.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(crate = "serde_crate")]
+struct TrieFields {
+    root: TrieDatalessNode,
+    len: usize,
+    layout: StorageLayout,
+    limits: TrieLimits,
+    node_count: usize,
+}
+
+#[cfg(feature = "serde")]
+impl From<TrieFields> for Trie {
+    fn from(mut fields: TrieFields) -> Self {
+        relayout_tree(&mut fields.root, fields.layout, |node| &mut node.children);
+
+        Trie {
+            root: fields.root,
+            len: fields.len,
+            layout: fields.layout,
+            limits: fields.limits,
+            node_count: fields.node_count,
+        }
+    }
 }
 
 impl Trie {
     /// Returns a new instance of the trie.
     pub fn new() -> Self {
+        Self::with_layout(StorageLayout::default())
+    }
+
+    /// Returns a new instance of the trie that stores the children
+    /// of its nodes according to 'layout'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{Trie, StorageLayout};
+    /// let layout = StorageLayout {
+    ///     promotion_threshold: 8,
+    ///     sorted_small: true,
+    ///     dense_ascii: false,
+    /// };
+    /// let mut trie = Trie::with_layout(layout);
+    ///
+    /// trie.insert("word");
+    /// assert!(trie.contains("word"));
+    /// assert_eq!(layout, trie.layout());
+    /// ```
+    pub fn with_layout(layout: StorageLayout) -> Self {
         Trie {
             root: TrieDatalessNode::new(),
             len: 0,
+            layout,
+            limits: TrieLimits::default(),
+            node_count: 0,
         }
     }
 
+    /// Returns a new instance of the trie whose insertions are bounded by 'limits'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{Trie, TrieError, TrieLimits};
+    /// let limits = TrieLimits {
+    ///     max_word_len: Some(8),
+    ///     max_words: Some(2),
+    ///     ..Default::default()
+    /// };
+    /// let mut trie = Trie::with_limits(limits);
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word1"));
+    /// assert_eq!(Err(TrieError::WordTooLong), trie.try_insert("longerword"));
+    /// assert_eq!(Ok(()), trie.try_insert("word2"));
+    /// assert_eq!(Err(TrieError::TooManyWords), trie.try_insert("word3"));
+    /// assert_eq!(limits, trie.limits());
+    /// ```
+    pub fn with_limits(limits: TrieLimits) -> Self {
+        let mut trie = Self::new();
+        trie.limits = limits;
+        trie
+    }
+
+    /// Returns the layout the trie stores the children of its nodes with.
+    pub fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
+    /// Returns the limits the insertions into the trie are bounded by.
+    pub fn limits(&self) -> TrieLimits {
+        self.limits
+    }
+
+    /// Sets the limits for the following insertions into the trie.
+    /// Words that are already in the trie are kept, even if they exceed the limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{StorageLayout, Trie, TrieError, TrieLimits};
+    /// let mut trie = Trie::with_layout(StorageLayout::default());
+    /// trie.insert("word");
+    ///
+    /// trie.set_limits(TrieLimits {
+    ///     max_nodes: Some(5),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("words"));
+    /// assert_eq!(Err(TrieError::CapacityExceeded), trie.try_insert("wordy"));
+    /// ```
+    pub fn set_limits(&mut self, limits: TrieLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the number of nodes in the trie, not counting the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("word");
+    /// trie.insert("words");
+    /// assert_eq!(5, trie.node_count());
+    /// ```
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
     /// Insert a word into the trie, with no corresponding data.
+    /// Tries with limits should insert with 'try_insert' instead. Here,
+    /// a word that exceeds the limits isn't inserted, and debug builds panic.
     ///
     /// # Examples
     ///
@@ -39,21 +200,56 @@ impl Trie {
     /// assert_eq!(vec![String::from("word1")], trie.get_all());
     /// ```
     pub fn insert(&mut self, word: &str) {
+        let inserted = self.try_insert(word);
+        debug_assert!(inserted.is_ok(), "the word exceeds the limits of the trie");
+    }
+
+    /// Insert a word into the trie, with no corresponding data, unless
+    /// that exceeds the limits of the trie. Before anything is inserted,
+    /// the number of characters of the word is checked, along with the number
+    /// of nodes, the memory they take and the number of words after the insertion.
+    /// The error names the exceeded limit, and nothing is inserted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{Trie, TrieError, TrieLimits};
+    /// let mut trie = Trie::with_limits(TrieLimits {
+    ///     max_word_len: Some(4),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(Ok(()), trie.try_insert("word"));
+    /// assert_eq!(Err(TrieError::WordTooLong), trie.try_insert("words"));
+    /// assert_eq!(vec![String::from("word")], trie.get_all());
+    /// ```
+    pub fn try_insert(&mut self, word: &str) -> Result<(), TrieError> {
+        self.check_limits(word)?;
+
+        // The word is counted on its path while walking it, and uncounted
+        // again in the rarer case of it already being in the trie.
         let mut current = &mut self.root;
+        current.increment_subtree_words();
 
         for character in get_characters(word) {
             if current.children.get_mut(character).is_none() {
-                current.children.insert_new(character);
+                current.children.insert_new(character, self.layout);
+                self.node_count += 1;
             }
 
             current = current.children.get_mut(character).unwrap();
+            current.increment_subtree_words();
         }
 
-        if !current.is_associated() {
-            self.len += 1;
+        if current.is_associated() {
+            self.root.subtract_path_words(get_characters(word), 1);
+            return Ok(());
         }
 
         current.associate();
+        self.len += 1;
+
+        Ok(())
     }
 
     /// Removes a word from the trie.
@@ -76,21 +272,39 @@ impl Trie {
     /// assert_eq!(Vec::<String>::new(), trie.get_all());
     /// ```
     pub fn remove(&mut self, word: &str) {
-        let Some(current) = self.get_final_node_mut(word) else {
+        if !self.contains(word) {
             return;
-        };
+        }
 
-        let characters = get_characters(word);
+        self.node_count -= self.root.remove_one_word(get_characters(word), self.layout);
+        self.len -= 1;
+    }
 
-        if !current.children.is_empty() {
-            return if current.is_associated() {
-                current.disassociate();
-                self.len -= 1;
-            };
-        }
+    /// Removes a word from the trie, reporting why the word couldn't be removed.
+    /// If no word begins with 'word', TrieError::NotFound is returned.
+    /// If the word is only a prefix to some word, TrieError::NotAWord is returned
+    /// and nothing is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{Trie, TrieError};
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("wording");
+    ///
+    /// assert_eq!(Err(TrieError::NotAWord), trie.try_remove("word"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_remove("other"));
+    /// assert_eq!(Ok(()), trie.try_remove("wording"));
+    /// assert!(trie.is_empty());
+    /// ```
+    pub fn try_remove(&mut self, word: &str) -> Result<(), TrieError> {
+        self.get_word_node(word)?;
 
-        self.root.remove_one_word(characters.into_iter());
+        self.node_count -= self.root.remove_one_word(get_characters(word), self.layout);
         self.len -= 1;
+
+        Ok(())
     }
 
     /// Removes every word that begins with 'prefix'.
@@ -117,11 +331,17 @@ impl Trie {
             return;
         };
 
-        // (current.is_associated() as usize) is added (subtracted twice) to
-        // not remove the current word from the count. Literal '1' is not used
-        // because of calling this function on the root node where 1 should
-        // not be added.
-        self.len -= current.remove_all_words() - (current.is_associated() as usize);
+        // (current.is_associated() as usize) is subtracted to not remove the
+        // current word from the count. Literal '1' is not used because of
+        // calling this function on the root node where 1 should not be subtracted.
+        let num_removed = current.subtree_words() - current.is_associated() as usize;
+        let removed_nodes = count_nodes(current);
+        current.clear_children();
+
+        self.root
+            .subtract_path_words(get_characters(prefix), num_removed);
+        self.len -= num_removed;
+        self.node_count -= removed_nodes;
     }
 
     /// Returns an option enum with a vector of owned strings
@@ -158,11 +378,290 @@ impl Trie {
         }
 
         let mut words_vec = Vec::new();
-        current_node.find_words(&mut substring, &mut words_vec);
+        find_words(current_node, &mut substring, &mut words_vec);
 
         Some(words_vec)
     }
 
+    /// Returns a vector of owned strings representing all found words
+    /// that begin with 'query', or TrieError::NotFound if there are none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{Trie, TrieError};
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("word");
+    ///
+    /// assert_eq!(Ok(vec![String::from("word")]), trie.try_get("wo"));
+    /// assert_eq!(Err(TrieError::NotFound), trie.try_get("other"));
+    /// ```
+    pub fn try_get(&self, query: &str) -> Result<Vec<String>, TrieError> {
+        self.get(query).ok_or(TrieError::NotFound)
+    }
+
+    /// Returns an iterator over the words that fall within 'range',
+    /// in lexicographic order. The iterator walks straight to the
+    /// lower bound and stops at the upper bound, so only the words
+    /// that are returned are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("lemon");
+    /// trie.insert("mango");
+    /// trie.insert("melon");
+    /// trie.insert("nectarine");
+    ///
+    /// let found_words = trie.range("m".."n").collect::<Vec<_>>();
+    /// assert_eq!(vec![String::from("mango"), String::from("melon")], found_words);
+    /// ```
+    pub fn range<'b, R: RangeBounds<&'b str>>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = String> + '_ {
+        WordRange::new(
+            &self.root,
+            range.start_bound().map(|word| *word),
+            range.end_bound().map(|word| *word),
+        )
+        .map(|(word, _)| word)
+    }
+
+    /// Returns an iterator over the words that are equal to or come after
+    /// 'word', in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("kite");
+    /// trie.insert("kites");
+    /// trie.insert("kiwi");
+    /// trie.insert("lime");
+    ///
+    /// let found_words = trie.range_from("kitf").take(2).collect::<Vec<_>>();
+    /// assert_eq!(vec![String::from("kiwi"), String::from("lime")], found_words);
+    /// ```
+    pub fn range_from(&self, word: &str) -> impl Iterator<Item = String> + '_ {
+        WordRange::new(&self.root, Bound::Included(word), Bound::Unbounded).map(|(word, _)| word)
+    }
+
+    /// Returns the position of 'word' in the lexicographic order of all words
+    /// in the trie, which is the number of words that come before it.
+    /// If the word is not found, None is returned.
+    /// Every node caches the number of words below it,
+    /// so only the path to the word is walked.
+    /// Requires the 'subtree-counts' feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("cherry");
+    /// trie.insert("apple");
+    /// trie.insert("banana");
+    ///
+    /// assert_eq!(Some(0), trie.rank("apple"));
+    /// assert_eq!(Some(2), trie.rank("cherry"));
+    /// assert_eq!(None, trie.rank("banan"));
+    /// ```
+    #[cfg(feature = "subtree-counts")]
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        rank(&self.root, word)
+    }
+
+    /// Returns the word at position 'n' (counting from 0) in the
+    /// lexicographic order of all words in the trie.
+    /// If the trie has 'n' words or fewer, None is returned.
+    /// Every node caches the number of words below it,
+    /// so only the path to the found word is walked.
+    /// Requires the 'subtree-counts' feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("cherry");
+    /// trie.insert("apple");
+    /// trie.insert("banana");
+    ///
+    /// assert_eq!(Some(String::from("banana")), trie.select(1));
+    /// assert_eq!(None, trie.select(3));
+    /// ```
+    #[cfg(feature = "subtree-counts")]
+    pub fn select(&self, n: usize) -> Option<String> {
+        select(&self.root, n)
+    }
+
+    /// Returns the words of the trie that are anagrams of 'letters', using every
+    /// one of them exactly once, in lexicographic order. Repeated letters
+    /// have to be repeated as many times in the word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("listen");
+    /// trie.insert("silent");
+    /// trie.insert("tinsel");
+    /// trie.insert("list");
+    ///
+    /// assert_eq!(vec!["listen", "silent", "tinsel"], trie.anagrams("enlist"));
+    /// assert!(trie.anagrams("tsil").contains(&String::from("list")));
+    /// ```
+    pub fn anagrams(&self, letters: &str) -> Vec<String> {
+        anagrams(&self.root, letters, 0, true)
+    }
+
+    /// Returns the words of the trie that can be spelled with some of 'letters'
+    /// and up to 'wildcards' blanks, in lexicographic order. Every letter and blank
+    /// is used at most once, and a blank stands for any character, like in word games.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("rat");
+    /// trie.insert("star");
+    /// trie.insert("tart");
+    /// trie.insert("zebra");
+    ///
+    /// assert_eq!(vec!["rat", "star"], trie.sub_anagrams("aeinrst", 0));
+    /// assert_eq!(vec!["rat", "star", "tart"], trie.sub_anagrams("aeinrst", 1));
+    /// ```
+    pub fn sub_anagrams(&self, letters: &str, wildcards: usize) -> Vec<String> {
+        anagrams(&self.root, letters, wildcards, false)
+    }
+
+    /// Returns every word of the trie spelled by a path of adjacent cells of 'grid',
+    /// where no cell is used twice in one path, like in Boggle. Words are returned
+    /// once each, in lexicographic order, with the (row, column) cells of a path
+    /// that spells them. Cells are compared exactly, without normalization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{Adjacency, Trie};
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("cat");
+    /// trie.insert("act");
+    /// trie.insert("tact");
+    ///
+    /// let grid = vec![
+    ///     vec!['c', 'a'],
+    ///     vec!['x', 't'],
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         (String::from("act"), vec![(0, 1), (0, 0), (1, 1)]),
+    ///         (String::from("cat"), vec![(0, 0), (0, 1), (1, 1)]),
+    ///     ],
+    ///     trie.find_in_grid(&grid, Adjacency::Eight)
+    /// );
+    ///
+    /// // Without diagonals, 'c' and 't' aren't adjacent.
+    /// let found_words = trie.find_in_grid(&grid, Adjacency::Four);
+    /// assert_eq!(vec![String::from("cat")], found_words.into_iter().map(|(word, _)| word).collect::<Vec<_>>());
+    /// ```
+    pub fn find_in_grid(
+        &self,
+        grid: &[Vec<char>],
+        adjacency: Adjacency,
+    ) -> Vec<(String, Vec<(usize, usize)>)> {
+        find_in_grid(&self.root, grid, adjacency)
+    }
+
+    /// Returns statistics on the structure of the trie and an estimate
+    /// of its heap memory usage. Every node is visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("eat");
+    /// trie.insert("eats");
+    /// trie.insert("tea");
+    ///
+    /// let stats = trie.stats();
+    /// assert_eq!(8, stats.node_count);
+    /// assert_eq!(3, stats.word_count);
+    /// assert_eq!(4, stats.max_depth);
+    /// assert_eq!(vec![2, 5, 1], stats.branching_histogram);
+    /// ```
+    pub fn stats(&self) -> TrieStats {
+        TrieStats::collect(&self.root)
+    }
+
+    /// Returns the description of the trie in the Graphviz DOT language.
+    /// Nodes that words end on are drawn as double circles and edges are
+    /// labeled with their characters, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("ab");
+    /// trie.insert("ac");
+    ///
+    /// let dot = trie.to_dot();
+    /// assert!(dot.starts_with("digraph trie {"));
+    /// assert!(dot.contains("n1 -> n2 [label=\"b\"];"));
+    /// assert!(dot.contains("n2 [shape=doublecircle, label=\"\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        render::to_dot(&self.root, |_| None)
+    }
+
+    /// Returns an indented drawing of the trie for debugging, one node per line,
+    /// that can be printed or formatted into a string. Children are ordered
+    /// by their character and word ends are marked with '*'. Nodes deeper than
+    /// 'max_depth' characters aren't drawn and drawing stops after 'max_nodes'
+    /// nodes; every skipped part is marked with '...'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("ab");
+    /// trie.insert("ac");
+    ///
+    /// let drawing = trie.display_tree(None, None).to_string();
+    /// assert_eq!("(root)\n└── a\n    ├── b *\n    └── c *\n", drawing);
+    ///
+    /// let drawing = trie.display_tree(Some(1), None).to_string();
+    /// assert_eq!("(root)\n└── a\n    └── ...\n", drawing);
+    /// ```
+    pub fn display_tree(
+        &self,
+        max_depth: Option<usize>,
+        max_nodes: Option<usize>,
+    ) -> impl fmt::Display + '_ {
+        TreeDisplay::new(&self.root, max_depth, max_nodes)
+    }
+
     /// Returns the vector of longest words found in the trie.
     ///
     /// # Examples
@@ -181,19 +680,7 @@ impl Trie {
     /// assert_eq!(longest_words, found_words);
     /// ```
     pub fn get_longest(&self) -> Vec<String> {
-        let mut words = Vec::new();
-        let mut collector = String::new();
-        let mut best_len = None;
-        self.root.words_min_max(
-            &mut collector,
-            0,
-            &mut words,
-            &mut best_len,
-            #[cfg(feature = "unicode")]
-            true,
-            Ordering::Greater,
-        );
-        words
+        words_min_max(&self.root, Ordering::Greater)
     }
 
     /// Returns the vector of shortest words found in the trie.
@@ -214,19 +701,7 @@ impl Trie {
     /// assert_eq!(shortest_word, found_words);
     /// ```
     pub fn get_shortest(&self) -> Vec<String> {
-        let mut words = Vec::new();
-        let mut collector = String::new();
-        let mut best_len = None;
-        self.root.words_min_max(
-            &mut collector,
-            0,
-            &mut words,
-            &mut best_len,
-            #[cfg(feature = "unicode")]
-            true,
-            Ordering::Less,
-        );
-        words
+        words_min_max(&self.root, Ordering::Less)
     }
 
     /// Returns the number of words in the trie.
@@ -255,6 +730,8 @@ impl Trie {
 
     /// Returns the number of words that start with 'prefix'.
     /// If the sequence 'prefix' is not found, None is returned.
+    /// With the 'subtree-counts' feature, the number is cached in the
+    /// prefix's node, so only the path to it is walked.
     ///
     /// # Examples
     /// ```
@@ -271,7 +748,7 @@ impl Trie {
     pub fn len_prefix(&self, prefix: &str) -> usize {
         match self.get_final_node(prefix) {
             None => 0,
-            Some(node) => node.count_words() - node.is_associated() as usize,
+            Some(node) => node.subtree_words() - node.is_associated() as usize,
         }
     }
 
@@ -357,8 +834,31 @@ impl Trie {
     /// assert_eq!(0, trie.len());
     /// ```
     pub fn clear(&mut self) {
-        self.root.clear_children();
+        self.root = TrieDatalessNode::new();
         self.len = 0;
+        self.node_count = 0;
+    }
+
+    /// Consumes the trie and returns an Aho-Corasick automaton for
+    /// finding occurrences of all its words inside some text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("tri");
+    /// trie.insert("trie");
+    ///
+    /// let matcher = trie.into_matcher();
+    /// assert_eq!(3, matcher.find_overlapping("a trie or a trip").len());
+    /// ```
+    pub fn into_matcher(self) -> Matcher {
+        Matcher::from_root(self.root, |node| {
+            let (children, word_end) = node.into_parts();
+            (children, word_end.then(ThinVec::new))
+        })
     }
 
     /// Function for getting the last node in a character sequence.
@@ -372,6 +872,39 @@ impl Trie {
         Some(current)
     }
 
+    /// Function checks that inserting a word stays within the limits of the trie.
+    fn check_limits(&self, word: &str) -> Result<(), TrieError> {
+        if self.limits.is_unlimited() {
+            return Ok(());
+        }
+
+        let (word_len, new_nodes, last) = insertion_path(&self.root, word);
+        let is_new_word = !last.is_some_and(|node| node.is_associated());
+
+        self.limits.check(Insertion {
+            word_len,
+            node_bytes: size_of::<(char, TrieDatalessNode)>(),
+            node_count: self.node_count,
+            new_nodes,
+            word_count: self.len,
+            new_words: is_new_word as usize,
+            data_count: 0,
+            new_data: 0,
+        })
+    }
+
+    /// Function for getting the last node of a word, or the reason
+    /// the character sequence isn't a word in the trie.
+    fn get_word_node(&self, word: &str) -> Result<&TrieDatalessNode, TrieError> {
+        let node = self.get_final_node(word).ok_or(TrieError::NotFound)?;
+
+        if node.is_associated() {
+            Ok(node)
+        } else {
+            Err(TrieError::NotAWord)
+        }
+    }
+
     /// Function for getting the last node in a character sequence (mutable).
     fn get_final_node_mut(&mut self, query: &str) -> Option<&mut TrieDatalessNode> {
         let mut current = &mut self.root;
@@ -384,11 +917,102 @@ impl Trie {
     }
 }
 
+#[cfg(feature = "regex")]
+impl Trie {
+    /// Returns all words in the trie that match the regular expression 'pattern'
+    /// as a whole, as if it were surrounded by '^' and '$'.
+    /// The pattern is compiled into a DFA that runs while the trie is walked,
+    /// so subtrees that no word matching the pattern can be in are never visited.
+    /// If the pattern can't be compiled, the boxed error is returned.
+    /// Order is not guaranteed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("unreadable");
+    /// trie.insert("reusable");
+    /// trie.insert("able");
+    /// trie.insert("unable");
+    ///
+    /// let mut found_words = trie.find_regex("^(un|re)[a-z]+able$").unwrap();
+    /// found_words.sort();
+    /// assert_eq!(vec![String::from("reusable"), String::from("unreadable")], found_words);
+    ///
+    /// assert!(trie.find_regex("(un").is_err());
+    /// ```
+    pub fn find_regex(
+        &self,
+        pattern: &str,
+    ) -> Result<Vec<String>, Box<regex_automata::dfa::dense::BuildError>> {
+        let mut found_words = Vec::new();
+        find_matching(&self.root, pattern, |_, word| {
+            found_words.push(String::from(word))
+        })?;
+
+        Ok(found_words)
+    }
+}
+
+#[cfg(feature = "fst")]
+impl Trie {
+    /// Returns an `fst::Set` containing every word in the trie.
+    /// Words are sorted by their bytes before building, as the
+    /// finite-state transducer format requires.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let mut trie = Trie::new();
+    ///
+    /// trie.insert("word2");
+    /// trie.insert("word1");
+    ///
+    /// let set = trie.to_fst_set().unwrap();
+    /// assert!(set.contains("word1"));
+    /// assert_eq!(2, set.len());
+    /// ```
+    pub fn to_fst_set(&self) -> Result<fst::Set<Vec<u8>>, fst::Error> {
+        let mut words = self.get_all();
+        words.sort_unstable();
+
+        fst::Set::from_iter(words)
+    }
+
+    /// Returns a new trie containing every key of an `fst::Set`.
+    /// If some key isn't valid UTF-8, the error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::Trie;
+    /// let set = fst::Set::from_iter(vec!["word1", "word2"]).unwrap();
+    ///
+    /// let trie = Trie::from_fst_set(&set).unwrap();
+    /// assert!(trie.contains("word1"));
+    /// assert_eq!(2, trie.len());
+    /// ```
+    pub fn from_fst_set<T: AsRef<[u8]>>(set: &fst::Set<T>) -> Result<Self, Utf8Error> {
+        let mut trie = Trie::new();
+        let mut stream = set.stream();
+
+        while let Some(key) = stream.next() {
+            trie.insert(core::str::from_utf8(key)?);
+        }
+
+        Ok(trie)
+    }
+}
+
 impl ops::Add for Trie {
     type Output = Trie;
 
     /// Operation + merges two tries, leaving out duplicate words.
-    /// The smaller trie is always added to the larger one for efficiency.
+    /// The smaller trie is always added to the larger one for efficiency,
+    /// and the result keeps the storage layout of the larger one.
     ///
     /// # Examples
     ///
@@ -420,10 +1044,11 @@ impl ops::Add for Trie {
             (rhs, self)
         };
 
-        bigger.root += smaller.root;
+        let merged_nodes = bigger.root.merge(smaller.root, bigger.layout);
+        bigger.node_count += smaller.node_count - merged_nodes;
 
         // Number of words needs to be recalculated.
-        bigger.len = bigger.root.count_words();
+        bigger.len = bigger.root.subtree_words();
 
         bigger
     }
@@ -431,6 +1056,7 @@ impl ops::Add for Trie {
 
 impl ops::AddAssign for Trie {
     /// Operation += merges two tries, leaving out duplicate words.
+    /// The limits of the left trie are kept, but aren't enforced on the merged words.
     ///
     /// # Examples
     ///
@@ -456,10 +1082,11 @@ impl ops::AddAssign for Trie {
     /// assert_eq!(trie_1, correct);
     /// ```
     fn add_assign(&mut self, rhs: Self) {
-        self.root += rhs.root;
+        let merged_nodes = self.root.merge(rhs.root, self.layout);
+        self.node_count += rhs.node_count - merged_nodes;
 
         // Number of words needs to be recalculated.
-        self.len = self.root.count_words();
+        self.len = self.root.subtree_words();
     }
 }
 
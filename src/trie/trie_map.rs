@@ -0,0 +1,481 @@
+use crate::child_storage::StorageLayout;
+use crate::trie::get_characters;
+use crate::trie_node::{TrieMapNode, WordRange};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Debug;
+use core::ops::{Bound, Index};
+
+#[cfg(feature = "serde")]
+use crate::child_storage::relayout_tree;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+/// Map from words to exactly one value each, like a `HashMap<String, V>`
+/// with prefix queries. Values are stored directly in the nodes,
+/// without the per-word vector of the data trie.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate", from = "TrieMapFields<V>")
+)]
+pub struct TrieMap<V> {
+    root: TrieMapNode<V>,
+    len: usize,
+    layout: StorageLayout,
+}
+
+/// Fields of a deserialized trie, whose children are laid out
+/// again with the layout of the trie.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(crate = "serde_crate")]
+struct TrieMapFields<V> {
+    root: TrieMapNode<V>,
+    len: usize,
+    layout: StorageLayout,
+}
+
+#[cfg(feature = "serde")]
+impl<V> From<TrieMapFields<V>> for TrieMap<V> {
+    fn from(mut fields: TrieMapFields<V>) -> Self {
+        relayout_tree(&mut fields.root, fields.layout, |node| &mut node.children);
+
+        TrieMap {
+            root: fields.root,
+            len: fields.len,
+            layout: fields.layout,
+        }
+    }
+}
+
+impl<V> Default for TrieMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> TrieMap<V> {
+    /// Returns a new instance of the trie.
+    pub fn new() -> Self {
+        Self::with_layout(StorageLayout::default())
+    }
+
+    /// Returns a new instance of the trie that stores the children
+    /// of its nodes according to 'layout'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::{StorageLayout, TrieMap};
+    /// let layout = StorageLayout {
+    ///     promotion_threshold: 8,
+    ///     sorted_small: true,
+    ///     dense_ascii: false,
+    /// };
+    /// let mut trie_map = TrieMap::with_layout(layout);
+    ///
+    /// trie_map.insert("word", 1);
+    /// assert_eq!(Some(&1), trie_map.get("word"));
+    /// assert_eq!(layout, trie_map.layout());
+    /// ```
+    pub fn with_layout(layout: StorageLayout) -> Self {
+        TrieMap {
+            root: TrieMapNode::new(),
+            len: 0,
+            layout,
+        }
+    }
+
+    /// Returns the layout the trie stores the children of its nodes with.
+    pub fn layout(&self) -> StorageLayout {
+        self.layout
+    }
+
+    /// Insert a word into the trie with its value.
+    /// If the word was already present, its value is replaced
+    /// and the old value is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// assert_eq!(None, trie_map.insert("word", 1));
+    /// assert_eq!(Some(1), trie_map.insert("word", 2));
+    /// assert_eq!(Some(&2), trie_map.get("word"));
+    /// ```
+    pub fn insert(&mut self, word: &str, value: V) -> Option<V> {
+        let mut current = &mut self.root;
+
+        for character in get_characters(word) {
+            if current.children.get_mut(character).is_none() {
+                current.children.insert_new(character, self.layout);
+            }
+
+            current = current.children.get_mut(character).unwrap();
+        }
+
+        let old_value = current.replace_value(value);
+
+        if old_value.is_none() {
+            self.len += 1;
+        }
+
+        old_value
+    }
+
+    /// Returns a reference to the value of a word.
+    /// If the word is not found, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 1);
+    /// assert_eq!(Some(&1), trie_map.get("word"));
+    /// assert_eq!(None, trie_map.get("wor"));
+    /// ```
+    pub fn get(&self, word: &str) -> Option<&V> {
+        self.get_final_node(word)?.value()
+    }
+
+    /// Returns a mutable reference to the value of a word.
+    /// If the word is not found, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 1);
+    /// *trie_map.get_mut("word").unwrap() += 10;
+    /// assert_eq!(Some(&11), trie_map.get("word"));
+    /// ```
+    pub fn get_mut(&mut self, word: &str) -> Option<&mut V> {
+        self.get_final_node_mut(word)?.value_mut()
+    }
+
+    /// Removes a word from the trie and returns its value.
+    /// If the word is a prefix to some word, some word isn't removed from the trie.
+    /// If the word is not found, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 1);
+    /// trie_map.insert("wording", 2);
+    ///
+    /// assert_eq!(Some(1), trie_map.remove("word"));
+    /// assert_eq!(None, trie_map.remove("word"));
+    /// assert_eq!(Some(&2), trie_map.get("wording"));
+    /// ```
+    pub fn remove(&mut self, word: &str) -> Option<V> {
+        let current = self.get_final_node_mut(word)?;
+
+        let value = if current.children.is_empty() {
+            self.root.remove_one_word(get_characters(word), self.layout)
+        } else {
+            current.take_value()
+        };
+
+        if value.is_some() {
+            self.len -= 1;
+        }
+
+        value
+    }
+
+    /// Returns an option enum with a vector of owned strings and references
+    /// to their values, representing all found words that begin with 'query'.
+    /// If the word 'query' doesn't exist, None is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word1", 1);
+    /// trie_map.insert("word2", 2);
+    ///
+    /// let mut found_entries = trie_map.get_prefix("word").unwrap();
+    /// found_entries.sort();
+    /// assert_eq!(vec![(String::from("word1"), &1), (String::from("word2"), &2)], found_entries);
+    /// ```
+    pub fn get_prefix(&self, query: &str) -> Option<Vec<(String, &V)>> {
+        let mut substring = String::new();
+        let mut current_node = &self.root;
+
+        for character in get_characters(query) {
+            current_node = current_node.children.get(character)?;
+            substring.push(character);
+        }
+
+        let mut entries_vec = Vec::new();
+        current_node.find_entries(&mut substring, &mut entries_vec);
+
+        Some(entries_vec)
+    }
+
+    /// Returns a vector of owned strings and references to their values,
+    /// representing all words in the trie.
+    /// Order is not guaranteed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 1);
+    /// assert_eq!(vec![(String::from("word"), &1)], trie_map.get_all());
+    /// ```
+    pub fn get_all(&self) -> Vec<(String, &V)> {
+        self.get_prefix("").unwrap()
+    }
+
+    /// Returns an iterator over all words in the trie and references
+    /// to their values, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word2", 2);
+    /// trie_map.insert("word1", 1);
+    ///
+    /// let entries = trie_map.iter().collect::<Vec<_>>();
+    /// assert_eq!(vec![(String::from("word1"), &1), (String::from("word2"), &2)], entries);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (String, &V)> + '_ {
+        WordRange::new(&self.root, Bound::Unbounded, Bound::Unbounded)
+            .filter_map(|(word, node)| Some((word, node.value()?)))
+    }
+
+    /// Returns an iterator over all words in the trie, in lexicographic order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let trie_map = [("word2", 2), ("word1", 1)].into_iter().collect::<TrieMap<_>>();
+    ///
+    /// assert_eq!(vec!["word1", "word2"], trie_map.keys().collect::<Vec<_>>());
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter().map(|(word, _)| word)
+    }
+
+    /// Returns an iterator over references to all values in the trie,
+    /// in the lexicographic order of their words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let trie_map = [("word2", 2), ("word1", 1)].into_iter().collect::<TrieMap<_>>();
+    ///
+    /// assert_eq!(vec![&1, &2], trie_map.values().collect::<Vec<_>>());
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns true if the trie contains 'query' as a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 0);
+    /// assert!(trie_map.contains_key("word"));
+    /// assert!(!trie_map.contains_key("notfound"));
+    /// ```
+    pub fn contains_key(&self, query: &str) -> bool {
+        self.get_final_node(query)
+            .is_some_and(|node| node.is_associated())
+    }
+
+    /// Returns the number of words in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word1", 1);
+    /// trie_map.insert("word2", 2);
+    /// trie_map.insert("word2", 3);
+    /// assert_eq!(2, trie_map.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no words are in the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 1);
+    /// trie_map.remove("word");
+    ///
+    /// assert!(trie_map.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all words from the trie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word1", 1);
+    /// trie_map.insert("word2", 2);
+    ///
+    /// trie_map.clear();
+    /// assert!(trie_map.is_empty());
+    /// assert_eq!(None, trie_map.get("word1"));
+    /// ```
+    pub fn clear(&mut self) {
+        self.root = TrieMapNode::new();
+        self.len = 0;
+    }
+
+    /// Function for getting the last node in a character sequence.
+    fn get_final_node(&self, query: &str) -> Option<&TrieMapNode<V>> {
+        let mut current = &self.root;
+
+        for character in get_characters(query) {
+            current = current.children.get(character)?;
+        }
+
+        Some(current)
+    }
+
+    /// Function for getting the last node in a character sequence (mutable).
+    fn get_final_node_mut(&mut self, query: &str) -> Option<&mut TrieMapNode<V>> {
+        let mut current = &mut self.root;
+
+        for character in get_characters(query) {
+            current = current.children.get_mut(character)?;
+        }
+
+        Some(current)
+    }
+}
+
+impl<K: AsRef<str>, V> Extend<(K, V)> for TrieMap<V> {
+    /// Inserts every word with its value, replacing the values of words
+    /// that were already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word1", 1);
+    /// trie_map.extend([("word1", 10), ("word2", 2)]);
+    /// assert_eq!(Some(&10), trie_map.get("word1"));
+    /// assert_eq!(2, trie_map.len());
+    /// ```
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (word, value) in iter {
+            self.insert(word.as_ref(), value);
+        }
+    }
+}
+
+impl<K: AsRef<str>, V> FromIterator<(K, V)> for TrieMap<V> {
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let trie_map = [("word1", 1), ("word2", 2)].into_iter().collect::<TrieMap<_>>();
+    ///
+    /// assert_eq!(Some(&2), trie_map.get("word2"));
+    /// ```
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie_map = TrieMap::new();
+        trie_map.extend(iter);
+        trie_map
+    }
+}
+
+impl<V> Index<&str> for TrieMap<V> {
+    type Output = V;
+
+    /// Returns a reference to the value of a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map = TrieMap::new();
+    ///
+    /// trie_map.insert("word", 1);
+    /// assert_eq!(1, trie_map["word"]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the word is not in the trie.
+    fn index(&self, word: &str) -> &V {
+        self.get(word).expect("word not found in the trie map")
+    }
+}
+
+impl<V: PartialEq> PartialEq for TrieMap<V> {
+    /// Operation '==' can be applied only to tries whose values implement PartialEq.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_trie::TrieMap;
+    /// let mut trie_map_1 = TrieMap::new();
+    /// trie_map_1.insert("test", 1);
+    ///
+    /// let mut trie_map_2 = TrieMap::new();
+    /// trie_map_2.insert("test", 1);
+    ///
+    /// assert_eq!(trie_map_1, trie_map_2);
+    ///
+    /// trie_map_2.insert("test", 2);
+    ///
+    /// assert_ne!(trie_map_1, trie_map_2);
+    /// ```
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.root == other.root
+    }
+}
+
+impl<V: Debug> Debug for TrieMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrieMap")
+            .field("len", &self.len)
+            .field("root", &self.root)
+            .finish()
+    }
+}
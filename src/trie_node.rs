@@ -1,9 +1,112 @@
 #[cfg(feature = "data")]
 mod data_node;
+#[cfg(feature = "data")]
+mod map_node;
 
+mod anagrams;
+mod arena_node;
+mod counting_node;
 mod regular_node;
+mod traversal;
+mod word_range;
+#[cfg(feature = "subtree-counts")]
+mod word_rank;
 
 #[cfg(feature = "data")]
 pub(crate) use data_node::TrieDataNode;
+#[cfg(feature = "data")]
+pub(crate) use map_node::TrieMapNode;
 
+pub(crate) use anagrams::anagrams;
+pub(crate) use arena_node::NodeArena;
+pub(crate) use counting_node::TrieCountingNode;
 pub(crate) use regular_node::TrieDatalessNode;
+#[cfg(feature = "regex")]
+pub(crate) use traversal::walk;
+pub(crate) use traversal::{count_nodes, find_words, words_min_max};
+pub(crate) use word_range::{WordRange, sorted_children};
+#[cfg(feature = "subtree-counts")]
+pub(crate) use word_rank::{rank, select};
+
+use crate::child_storage::{ChildStorage, StorageLayout};
+
+/// Read access shared by the nodes of every trie, used by traversals
+/// that are the same for all of them.
+pub(crate) trait TrieNode: Sized {
+    /// Function returns the children of the node.
+    fn children(&self) -> &ChildStorage<Self>;
+
+    /// Function returns the children of the node, for changing them.
+    fn children_mut(&mut self) -> &mut ChildStorage<Self>;
+
+    /// Function returns true if the node is an end of a word.
+    fn is_associated(&self) -> bool;
+
+    /// Function returns the cached number of words in the subtree of the node,
+    /// including the word ending on the node itself.
+    fn subtree_words(&self) -> usize;
+
+    /// Function returns an estimate of the heap memory held by the node itself,
+    /// apart from its children.
+    fn association_heap_bytes(&self) -> usize {
+        0
+    }
+
+    /// Function returns true if the node has to be kept when a word that continues
+    /// below it is removed, because another word ends on it or branches off from it.
+    fn is_needed(&self) -> bool {
+        self.is_associated() || self.children().len() > 1
+    }
+
+    /// Function for removing and freeing memory of a word that is not needed anymore.
+    /// The algorithm first walks the path of a word given as its characters
+    /// to find the last node that should not be deleted, then it frees the nodes after it.
+    /// The last node that should not be deleted is either:
+    /// - the root node
+    /// - the node that is still needed by other words
+    /// - the last node of the word, if longer words continue from it
+    ///
+    /// The last node of the word is handed to 'take_word_end', either in the trie or
+    /// after the freed nodes are walked to it, and the result is returned along with
+    /// the number of freed nodes. The path of the word must be in the trie.
+    fn remove_word_path<R>(
+        &mut self,
+        characters: &[char],
+        layout: StorageLayout,
+        take_word_end: impl FnOnce(&mut Self) -> R,
+    ) -> (R, usize) {
+        let mut kept_depth = 0;
+        let mut current = &*self;
+
+        for (depth, &character) in characters.iter().enumerate() {
+            if current.is_needed() {
+                kept_depth = depth;
+            }
+
+            current = current.children().get(character).unwrap();
+        }
+
+        if !current.children().is_empty() {
+            kept_depth = characters.len();
+        }
+
+        let mut current = self;
+
+        for &character in &characters[..kept_depth] {
+            current = current.children_mut().get_mut(character).unwrap();
+        }
+
+        let Some(&character) = characters.get(kept_depth) else {
+            return (take_word_end(current), 0);
+        };
+
+        let mut removed = current.children_mut().remove(character, layout).unwrap();
+
+        for &character in &characters[kept_depth + 1..] {
+            let next = removed.children_mut().remove(character, layout).unwrap();
+            removed = next;
+        }
+
+        (take_word_end(&mut removed), characters.len() - kept_depth)
+    }
+}
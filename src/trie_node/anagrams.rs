@@ -0,0 +1,94 @@
+use crate::trie::get_characters;
+use crate::trie_node::TrieNode;
+use crate::trie_node::sorted_children;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Function returns, in lexicographic order, the words of the trie with the given root
+/// that can be spelled with 'letters' and 'wildcards' blanks, where every letter and
+/// blank is used at most once and a blank stands for any character. When 'use_all'
+/// is true, only the words that use every letter and blank are returned.
+///
+/// The trie is walked depth first while the letters are taken out of a counted multiset,
+/// so a branch is left as soon as its character can't be taken from what is left.
+/// A character is taken from the letters before falling back to a blank, since a blank
+/// can stand for anything a letter can.
+pub(crate) fn anagrams<N: TrieNode>(
+    root: &N,
+    letters: &str,
+    wildcards: usize,
+    use_all: bool,
+) -> Vec<String> {
+    let mut counts = BTreeMap::<char, usize>::new();
+    for letter in get_characters(letters) {
+        *counts.entry(letter).or_default() += 1;
+    }
+
+    let mut left = counts.values().sum::<usize>() + wildcards;
+    let mut wildcards_left = wildcards;
+
+    // Characters of the current word, and whether they were taken from a blank.
+    let mut word = String::new();
+    let mut taken = Vec::<bool>::new();
+    let mut found_words = Vec::new();
+
+    if root.is_associated() && (!use_all || left == 0) {
+        found_words.push(String::new());
+    }
+
+    let mut stack = children_at(root, 1, left);
+
+    while let Some((character, node, depth)) = stack.pop() {
+        // Everything taken below the parent of the node is given back.
+        while taken.len() >= depth {
+            let returned = word.pop().unwrap();
+
+            if taken.pop().unwrap() {
+                wildcards_left += 1;
+            } else {
+                *counts.get_mut(&returned).unwrap() += 1;
+            }
+
+            left += 1;
+        }
+
+        match counts.get_mut(&character) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                taken.push(false);
+            }
+            _ if wildcards_left > 0 => {
+                wildcards_left -= 1;
+                taken.push(true);
+            }
+            _ => continue,
+        }
+
+        word.push(character);
+        left -= 1;
+
+        if node.is_associated() && (!use_all || left == 0) {
+            found_words.push(word.clone());
+        }
+
+        stack.extend(children_at(node, depth + 1, left));
+    }
+
+    found_words
+}
+
+/// Function returns the children of a node with their characters and 'depth',
+/// in reverse lexicographic order so that they are popped in order,
+/// or nothing if there is nothing 'left' to spell them with.
+fn children_at<N: TrieNode>(node: &N, depth: usize, left: usize) -> Vec<(char, &N, usize)> {
+    if left == 0 {
+        return Vec::new();
+    }
+
+    sorted_children(node)
+        .into_iter()
+        .rev()
+        .map(|(character, child)| (character, child, depth))
+        .collect()
+}
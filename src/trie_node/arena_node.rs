@@ -0,0 +1,524 @@
+use crate::TrieError;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+use thin_vec::ThinVec;
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Index marking a missing child or sibling.
+const NONE: u32 = u32::MAX;
+
+/// Index of the root node, which is never freed.
+const ROOT: u32 = 0;
+
+/// Trie node that lives inside of an arena and refers to its first child
+/// and its next sibling by their indices, so it allocates nothing for its children.
+/// Siblings are kept sorted by their characters.
+/// The data vector is present only when the node is an end of a word.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug)]
+struct ArenaNode<D> {
+    #[cfg_attr(feature = "serde", serde(rename = "ch"))]
+    character: char,
+    #[cfg_attr(feature = "serde", serde(rename = "fc"))]
+    first_child: u32,
+    #[cfg_attr(feature = "serde", serde(rename = "ns"))]
+    next_sibling: u32,
+    #[cfg_attr(feature = "serde", serde(rename = "wd"))]
+    data: Option<ThinVec<D>>,
+}
+
+impl<D> ArenaNode<D> {
+    fn new(character: char, next_sibling: u32) -> Self {
+        ArenaNode {
+            character,
+            first_child: NONE,
+            next_sibling,
+            data: None,
+        }
+    }
+}
+
+/// Every node of a trie in a single vector, with the root at index 0.
+/// Freed nodes are chained through their siblings and reused by later insertions,
+/// so the vector only grows when there are no freed nodes left.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Debug)]
+pub(crate) struct NodeArena<D> {
+    #[cfg_attr(feature = "serde", serde(rename = "n"))]
+    nodes: Vec<ArenaNode<D>>,
+    #[cfg_attr(feature = "serde", serde(rename = "f"))]
+    free: u32,
+}
+
+impl<D> NodeArena<D> {
+    /// Returns a new arena holding only the root, with room for 'capacity' nodes.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let mut nodes = Vec::with_capacity(capacity.max(1));
+        nodes.push(ArenaNode::new('\0', NONE));
+
+        NodeArena { nodes, free: NONE }
+    }
+
+    /// Function removes every node apart from the root, keeping the buffer.
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.nodes.push(ArenaNode::new('\0', NONE));
+        self.free = NONE;
+    }
+
+    /// Function returns the child of a node with the given character.
+    pub(crate) fn child(&self, parent: u32, character: char) -> Option<u32> {
+        let mut current = self.nodes[parent as usize].first_child;
+
+        while current != NONE {
+            let node = &self.nodes[current as usize];
+
+            if node.character >= character {
+                return (node.character == character).then_some(current);
+            }
+
+            current = node.next_sibling;
+        }
+
+        None
+    }
+
+    /// Function returns the children of a node in the order of their characters.
+    fn children(&self, parent: u32) -> impl Iterator<Item = u32> + '_ {
+        let first = self.nodes[parent as usize].first_child;
+
+        core::iter::successors((first != NONE).then_some(first), |&current| {
+            let next = self.nodes[current as usize].next_sibling;
+            (next != NONE).then_some(next)
+        })
+    }
+
+    /// Function returns the last node of a character sequence.
+    pub(crate) fn find(&self, characters: impl Iterator<Item = char>) -> Option<u32> {
+        let mut current = ROOT;
+
+        for character in characters {
+            current = self.child(current, character)?;
+        }
+
+        Some(current)
+    }
+
+    /// Function returns the last node of a character sequence,
+    /// inserting the nodes that are missing on the way.
+    pub(crate) fn find_or_insert(&mut self, characters: impl Iterator<Item = char>) -> u32 {
+        let mut current = ROOT;
+
+        for character in characters {
+            current = self.child_or_insert(current, character);
+        }
+
+        current
+    }
+
+    /// Function returns the last node of a character sequence, inserting the nodes
+    /// that are missing on the way. If the arena can't hold all of the missing nodes,
+    /// nothing is inserted and TrieError::CapacityExceeded is returned.
+    pub(crate) fn try_find_or_insert(
+        &mut self,
+        characters: impl Iterator<Item = char>,
+    ) -> Result<u32, TrieError> {
+        let characters = characters.collect::<Vec<_>>();
+        let mut current = ROOT;
+        let mut depth = 0;
+
+        while let Some(child) = characters
+            .get(depth)
+            .and_then(|&character| self.child(current, character))
+        {
+            current = child;
+            depth += 1;
+        }
+
+        if !self.has_room_for(characters.len() - depth) {
+            return Err(TrieError::CapacityExceeded);
+        }
+
+        for &character in &characters[depth..] {
+            current = self.child_or_insert(current, character);
+        }
+
+        Ok(current)
+    }
+
+    /// Function returns the last node of a word, or the reason
+    /// the character sequence isn't a word in the arena.
+    pub(crate) fn find_word(
+        &self,
+        characters: impl Iterator<Item = char>,
+    ) -> Result<u32, TrieError> {
+        let node = self.find(characters).ok_or(TrieError::NotFound)?;
+
+        if self.is_associated(node) {
+            Ok(node)
+        } else {
+            Err(TrieError::NotAWord)
+        }
+    }
+
+    /// Function returns the child of a node with the given character,
+    /// inserting it among its siblings if it's missing.
+    fn child_or_insert(&mut self, parent: u32, character: char) -> u32 {
+        let mut previous = NONE;
+        let mut current = self.nodes[parent as usize].first_child;
+
+        while current != NONE {
+            let node = &self.nodes[current as usize];
+
+            if node.character == character {
+                return current;
+            }
+            if node.character > character {
+                break;
+            }
+
+            previous = current;
+            current = node.next_sibling;
+        }
+
+        let inserted = self.allocate(ArenaNode::new(character, current));
+
+        if previous == NONE {
+            self.nodes[parent as usize].first_child = inserted;
+        } else {
+            self.nodes[previous as usize].next_sibling = inserted;
+        }
+
+        inserted
+    }
+
+    /// Function places a node into a freed slot or at the end of the arena.
+    fn allocate(&mut self, node: ArenaNode<D>) -> u32 {
+        if self.free != NONE {
+            let index = self.free;
+            self.free = self.nodes[index as usize].next_sibling;
+            self.nodes[index as usize] = node;
+
+            return index;
+        }
+
+        let index = u32::try_from(self.nodes.len())
+            .ok()
+            .filter(|&index| index != NONE)
+            .expect("the arena can't hold more than u32::MAX - 1 nodes");
+        self.nodes.push(node);
+
+        index
+    }
+
+    /// Function returns true if 'count' more nodes can be allocated. Freed slots
+    /// are only counted when the end of the arena doesn't have room for all of them.
+    fn has_room_for(&self, count: usize) -> bool {
+        let unused = NONE as usize - self.nodes.len();
+        if count <= unused {
+            return true;
+        }
+
+        let mut free_slots = 0;
+        let mut current = self.free;
+
+        while current != NONE && unused + free_slots < count {
+            free_slots += 1;
+            current = self.nodes[current as usize].next_sibling;
+        }
+
+        unused + free_slots >= count
+    }
+
+    /// Function frees a node that is no longer linked from its parent.
+    fn release(&mut self, index: u32) {
+        let node = &mut self.nodes[index as usize];
+        node.data = None;
+        node.first_child = NONE;
+        node.next_sibling = self.free;
+        self.free = index;
+    }
+
+    /// Function unlinks a child from the children of its parent.
+    fn unlink(&mut self, parent: u32, child: u32) {
+        let next = self.nodes[child as usize].next_sibling;
+
+        if self.nodes[parent as usize].first_child == child {
+            self.nodes[parent as usize].first_child = next;
+            return;
+        }
+
+        let mut current = self.nodes[parent as usize].first_child;
+        while self.nodes[current as usize].next_sibling != child {
+            current = self.nodes[current as usize].next_sibling;
+        }
+        self.nodes[current as usize].next_sibling = next;
+    }
+
+    /// Function returns the data of a node (mutable).
+    pub(crate) fn data_mut(&mut self, index: u32) -> &mut Option<ThinVec<D>> {
+        &mut self.nodes[index as usize].data
+    }
+
+    /// Function returns true if the node is an end of a word.
+    pub(crate) fn is_associated(&self, index: u32) -> bool {
+        self.nodes[index as usize].data.is_some()
+    }
+
+    /// Function removes the word ending on the last node of a character sequence,
+    /// and frees the nodes that were left with no words in their subtree.
+    /// Returns the data of the word, or None if the sequence isn't a word.
+    pub(crate) fn remove_word(
+        &mut self,
+        characters: impl Iterator<Item = char>,
+    ) -> Option<ThinVec<D>> {
+        let mut path = vec![ROOT];
+
+        for character in characters {
+            path.push(self.child(*path.last().unwrap(), character)?);
+        }
+
+        let data = self.nodes[*path.last().unwrap() as usize].data.take()?;
+
+        while let [.., parent, node] = path[..] {
+            let removed = &self.nodes[node as usize];
+            if removed.first_child != NONE || removed.data.is_some() {
+                break;
+            }
+
+            self.unlink(parent, node);
+            self.release(node);
+            path.pop();
+        }
+
+        Some(data)
+    }
+
+    /// Function frees every descendant of a node, passing the data of every
+    /// word ending on them to 'collect'. Returns the number of removed words.
+    pub(crate) fn remove_descendants(&mut self, index: u32, mut collect: impl FnMut(D)) -> usize {
+        let mut stack = self.children(index).collect::<Vec<_>>();
+        self.nodes[index as usize].first_child = NONE;
+        let mut word_count = 0;
+
+        while let Some(current) = stack.pop() {
+            stack.extend(self.children(current));
+
+            if let Some(data) = self.nodes[current as usize].data.take() {
+                word_count += 1;
+                data.into_iter().for_each(&mut collect);
+            }
+
+            self.release(current);
+        }
+
+        word_count
+    }
+
+    /// Function returns every word in the subtree of a node, together with
+    /// the node it ends on, in lexicographic order. 'prefix' is the word of the node.
+    pub(crate) fn words(&self, index: u32, prefix: String) -> Vec<(String, u32)> {
+        let mut words = Vec::new();
+        let mut word = prefix;
+        let mut stack = vec![(index, word.len())];
+
+        while let Some((current, word_len)) = stack.pop() {
+            word.truncate(word_len);
+            if current != index {
+                word.push(self.nodes[current as usize].character);
+            }
+
+            if self.is_associated(current) {
+                words.push((word.clone(), current));
+            }
+
+            let children_start = stack.len();
+            stack.extend(self.children(current).map(|child| (child, word.len())));
+            stack[children_start..].reverse();
+        }
+
+        words
+    }
+
+    /// Function returns the number of words in the subtree of a node,
+    /// including the word ending on the node itself.
+    pub(crate) fn count_words(&self, index: u32) -> usize {
+        let mut word_count = 0;
+        let mut stack = vec![index];
+
+        while let Some(current) = stack.pop() {
+            word_count += self.is_associated(current) as usize;
+            stack.extend(self.children(current));
+        }
+
+        word_count
+    }
+
+    /// Function returns the longest words when 'longest' is true,
+    /// or the shortest words otherwise, measured in graphemes.
+    pub(crate) fn words_min_max(&self, longest: bool) -> Vec<String> {
+        let words = self.words(ROOT, String::new());
+        let lengths = words
+            .iter()
+            .map(|(word, _)| visual_len(word))
+            .collect::<Vec<_>>();
+
+        let best_len = if longest {
+            lengths.iter().max()
+        } else {
+            lengths.iter().min()
+        };
+
+        words
+            .into_iter()
+            .zip(&lengths)
+            .filter(|(_, len)| Some(*len) == best_len)
+            .map(|((word, _), _)| word)
+            .collect()
+    }
+
+    /// Function moves every word of 'rhs' into this arena, appending the data
+    /// of the words that are in both. Returns the number of words that were new.
+    pub(crate) fn merge(&mut self, mut rhs: NodeArena<D>) -> usize {
+        let mut new_words = 0;
+        let mut stack = vec![(ROOT, ROOT)];
+
+        while let Some((rhs_current, self_current)) = stack.pop() {
+            if let Some(rhs_data) = rhs.nodes[rhs_current as usize].data.take() {
+                match &mut self.nodes[self_current as usize].data {
+                    Some(self_data) => self_data.extend(rhs_data),
+                    self_data @ None => {
+                        *self_data = Some(rhs_data);
+                        new_words += 1;
+                    }
+                }
+            }
+
+            for rhs_child in rhs.children(rhs_current) {
+                let character = rhs.nodes[rhs_child as usize].character;
+                stack.push((rhs_child, self.child_or_insert(self_current, character)));
+            }
+        }
+
+        new_words
+    }
+}
+
+#[cfg(feature = "data")]
+impl<D> NodeArena<D> {
+    /// Function returns the data of a node, which is present when the node is an end of a word.
+    pub(crate) fn data(&self, index: u32) -> Option<&ThinVec<D>> {
+        self.nodes[index as usize].data.as_ref()
+    }
+
+    /// Function returns the data of every word in the subtree of a node.
+    pub(crate) fn subtree_data(&self, index: u32) -> Vec<&D> {
+        self.words(index, String::new())
+            .into_iter()
+            .flat_map(|(_, node)| self.nodes[node as usize].data.iter().flatten())
+            .collect()
+    }
+
+    /// Function returns the data of every word in the subtree of a node (mutable).
+    /// Only the subtree is walked, and the nodes of its words are split off
+    /// the arena in the order of their indices, so their borrows don't overlap.
+    pub(crate) fn subtree_data_mut(&mut self, index: u32) -> Vec<&mut D> {
+        let mut word_nodes = Vec::new();
+        let mut stack = vec![index];
+
+        while let Some(current) = stack.pop() {
+            if self.is_associated(current) {
+                word_nodes.push(current as usize);
+            }
+
+            stack.extend(self.children(current));
+        }
+
+        word_nodes.sort_unstable();
+
+        let mut found_data = Vec::new();
+        let mut rest = self.nodes.as_mut_slice();
+        let mut offset = 0;
+
+        for node in word_nodes {
+            let (head, tail) = core::mem::take(&mut rest).split_at_mut(node - offset + 1);
+            found_data.extend(head.last_mut().unwrap().data.iter_mut().flatten());
+
+            rest = tail;
+            offset = node + 1;
+        }
+
+        found_data
+    }
+}
+
+impl<D: PartialEq> PartialEq for NodeArena<D> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(ROOT, ROOT)];
+
+        while let Some((self_current, other_current)) = stack.pop() {
+            let data_eq = match (
+                &self.nodes[self_current as usize].data,
+                &other.nodes[other_current as usize].data,
+            ) {
+                // Data is compared the same way as in the data trie, regardless of its order.
+                (Some(self_vec), Some(other_vec)) => {
+                    self_vec.len() == other_vec.len()
+                        && self_vec.iter().all(|k| other_vec.contains(k))
+                }
+                (None, None) => true,
+                _ => false,
+            };
+
+            if !data_eq {
+                return false;
+            }
+
+            let mut self_children = self.children(self_current);
+            let mut other_children = other.children(other_current);
+
+            loop {
+                match (self_children.next(), other_children.next()) {
+                    (None, None) => break,
+                    (Some(self_child), Some(other_child))
+                        if self.nodes[self_child as usize].character
+                            == other.nodes[other_child as usize].character =>
+                    {
+                        stack.push((self_child, other_child));
+                    }
+                    _ => return false,
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Function returns the number of graphemes in a word.
+#[cfg(feature = "unicode")]
+fn visual_len(word: &str) -> usize {
+    if word.is_ascii() {
+        word.len()
+    } else {
+        word.graphemes(true).count()
+    }
+}
+
+/// Function returns the number of characters in a word.
+#[cfg(not(feature = "unicode"))]
+fn visual_len(word: &str) -> usize {
+    word.chars().count()
+}
@@ -0,0 +1,190 @@
+use alloc::collections::BinaryHeap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::child_storage::{ChildStorage, StorageLayout};
+use crate::trie_node::traversal::{drop_children, nodes_eq, walk};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+/// Heap entry for finding the most frequent words. The heap is a min-heap over
+/// counts, and among equal counts the lexicographically largest word is evicted first.
+pub(crate) type FrequentEntry = Reverse<(usize, Reverse<String>)>;
+
+/// Singular trie node that represents its children, the number of times
+/// the word ending on it was inserted and the number of times any word
+/// in its subtree was inserted.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+#[derive(Default, Debug)]
+pub struct TrieCountingNode {
+    #[cfg_attr(feature = "serde", serde(rename = "c"))]
+    pub(crate) children: ChildStorage<TrieCountingNode>,
+    #[cfg_attr(feature = "serde", serde(rename = "wc"))]
+    word_count: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "sc"))]
+    subtree_count: usize,
+}
+
+impl TrieCountingNode {
+    /// Returns a new instance of a TrieNode.
+    pub(crate) fn new() -> Self {
+        TrieCountingNode {
+            children: Default::default(),
+            word_count: 0,
+            subtree_count: 0,
+        }
+    }
+
+    /// Function for adding 'n' occurrences of a word given in the form of
+    /// a character iterator. Every node on the path caches the new occurrences,
+    /// and missing nodes are stored according to 'layout'.
+    /// Returns the number of occurrences of the word before adding.
+    pub(crate) fn add_occurrences(
+        &mut self,
+        characters: impl Iterator<Item = char>,
+        n: usize,
+        layout: StorageLayout,
+    ) -> usize {
+        let mut current = self;
+        current.subtree_count += n;
+
+        for character in characters {
+            if current.children.get_mut(character).is_none() {
+                current.children.insert_new(character, layout);
+            }
+
+            current = current.children.get_mut(character).unwrap();
+            current.subtree_count += n;
+        }
+
+        let previous_count = current.word_count;
+        current.word_count += n;
+
+        previous_count
+    }
+
+    /// Function for removing 'n' occurrences of a word that is known to be
+    /// in the trie with at least 'n' occurrences. The occurrences are removed
+    /// from the cached counts on the path. The first node on the path whose
+    /// subtree is left with no occurrences is freed with all of its children,
+    /// since nothing below it is a word anymore.
+    pub(crate) fn remove_occurrences(
+        &mut self,
+        characters: impl Iterator<Item = char>,
+        n: usize,
+        layout: StorageLayout,
+    ) {
+        let mut current = self;
+        current.subtree_count -= n;
+
+        for character in characters {
+            if current.children.get(character).unwrap().subtree_count == n {
+                current.children.remove(character, layout);
+                return;
+            }
+
+            current = current.children.get_mut(character).unwrap();
+            current.subtree_count -= n;
+        }
+
+        current.word_count -= n;
+    }
+
+    /// Function for inserting found words and their counts
+    /// from the given node and given starting substring.
+    pub(crate) fn find_words(
+        &self,
+        substring: &mut String,
+        found_words: &mut Vec<(String, usize)>,
+    ) {
+        walk(
+            self,
+            substring,
+            (),
+            |node| &node.children,
+            |node, _, word, _| {
+                if node.is_associated() {
+                    found_words.push((word.to_string(), node.word_count));
+                }
+
+                Some(())
+            },
+        );
+    }
+
+    /// Function for keeping the 'k' most frequent words in the heap.
+    /// Once the heap is full, a subtree is skipped when its total count can't
+    /// beat the least frequent word in the heap, since no single word in it
+    /// has more occurrences than the whole subtree.
+    pub(crate) fn most_frequent(
+        &self,
+        substring: &mut String,
+        k: usize,
+        heap: &mut BinaryHeap<FrequentEntry>,
+    ) {
+        walk(
+            self,
+            substring,
+            (),
+            |node| &node.children,
+            |node, _, word, _| {
+                if heap.len() == k
+                    && heap
+                        .peek()
+                        .is_some_and(|Reverse((min, _))| node.subtree_count < *min)
+                {
+                    return None;
+                }
+
+                if node.is_associated() {
+                    heap.push(Reverse((node.word_count, Reverse(word.to_string()))));
+
+                    if heap.len() > k {
+                        heap.pop();
+                    }
+                }
+
+                Some(())
+            },
+        );
+    }
+
+    /// Function returns the number of times the word ending on this node was inserted.
+    pub(crate) fn word_count(&self) -> usize {
+        self.word_count
+    }
+
+    /// Function returns the number of times any word in this subtree was inserted.
+    pub(crate) fn subtree_count(&self) -> usize {
+        self.subtree_count
+    }
+
+    /// Function returns true if the node is an end of a word.
+    pub(crate) fn is_associated(&self) -> bool {
+        self.word_count > 0
+    }
+}
+
+impl Drop for TrieCountingNode {
+    fn drop(&mut self) {
+        drop_children(&mut self.children, |node| &mut node.children);
+    }
+}
+
+impl PartialEq for TrieCountingNode {
+    fn eq(&self, other: &Self) -> bool {
+        // If the words on two nodes weren't inserted the same number of times, nodes aren't equal.
+        // Equal children imply equal subtree counts, so those aren't compared.
+        nodes_eq(
+            self,
+            other,
+            |node| &node.children,
+            |node, other| node.word_count == other.word_count,
+        )
+    }
+}
@@ -1,25 +1,23 @@
-use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::{fmt, ops};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Debug;
 use thin_vec::ThinVec;
 
-use crate::child_storage::ChildStorage;
+use crate::child_storage::{ChildStorage, StorageLayout};
+use crate::trie_node::TrieNode;
+#[cfg(not(feature = "subtree-counts"))]
+use crate::trie_node::traversal::count_words;
+use crate::trie_node::traversal::{drop_children, merged_new_words, nodes_eq};
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
-use unicode_segmentation::UnicodeSegmentation;
 
 type WordEnd<D> = Option<ThinVec<D>>;
 
-/// Helper struct for returning multiple values for deleting data.
-/// It is needed because the 'must_keep' value will at some point change
-/// from false to true, but the data stays the same from the beginning of
-/// unwinding.
-pub(crate) struct RemoveData<D> {
-    must_keep: bool,
-    pub(crate) data: WordEnd<D>,
-}
-
-/// Singular trie node that represents its children and a marker for word ending.
+/// Singular trie node that represents its children, a marker for word ending
+/// with the data of the word and, with the 'subtree-counts' feature,
+/// the cached number of words in its subtree.
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -30,6 +28,9 @@ pub struct TrieDataNode<D> {
     pub(crate) children: ChildStorage<TrieDataNode<D>>,
     #[cfg_attr(feature = "serde", serde(rename = "wed"))]
     word_end_data: WordEnd<D>,
+    #[cfg(feature = "subtree-counts")]
+    #[cfg_attr(feature = "serde", serde(rename = "sw"))]
+    subtree_words: usize,
 }
 
 impl<D> Default for TrieDataNode<D> {
@@ -37,6 +38,8 @@ impl<D> Default for TrieDataNode<D> {
         Self {
             children: ChildStorage::default(),
             word_end_data: None,
+            #[cfg(feature = "subtree-counts")]
+            subtree_words: 0,
         }
     }
 }
@@ -48,136 +51,185 @@ impl<D> TrieDataNode<D> {
         TrieDataNode {
             children: Default::default(),
             word_end_data: None,
+            #[cfg(feature = "subtree-counts")]
+            subtree_words: 0,
         }
     }
 
-    /// Recursive function that drops all children maps and collects data
-    /// regardless of having multiple words branching from them or not.
-    pub(crate) fn remove_all_words_collect(&mut self, found_data: &mut Vec<D>) -> usize {
-        let num_removed = self
-            .children
-            .values_mut()
-            .map(|child| child.remove_all_words_collect(found_data))
-            .sum::<usize>()
-            + self.is_associated() as usize;
-
-        if let Some(data_vec) = self.disassociate() {
-            found_data.extend(data_vec);
-        }
+    /// Function drops all children of the node and collects the data of every word
+    /// below it, regardless of having multiple words branching from them or not.
+    /// Returns the number of removed words.
+    pub(crate) fn remove_descendants_collect(&mut self, found_data: &mut Vec<D>) -> usize {
+        let mut num_removed = 0;
+        let mut stack = core::mem::take(&mut self.children)
+            .into_iter()
+            .map(|(_, child)| child)
+            .collect::<Vec<_>>();
+
+        while let Some(mut node) = stack.pop() {
+            if let Some(data_vec) = node.disassociate() {
+                found_data.extend(data_vec);
+                num_removed += 1;
+            }
 
-        self.clear_children();
+            stack.extend(
+                core::mem::take(&mut node.children)
+                    .into_iter()
+                    .map(|(_, child)| child),
+            );
+        }
 
         num_removed
     }
 
-    /// Recursive function that counts the number of words from a starting node.
-    pub(crate) fn count_words(&self) -> usize {
-        self.children
-            .values()
-            .map(|child| child.count_words())
-            .sum::<usize>()
-            + self.is_associated() as usize
-    }
-
-    /// Recursive function finds every node that is an end of a word and appends
+    /// Function finds every node that is an end of a word and appends
     /// its data as references to the passed vector.
     pub(crate) fn generate_all_data<'a>(&'a self, found_data: &mut Vec<&'a D>) {
-        if let Some(data_vec) = &self.word_end_data {
-            found_data.extend(data_vec.iter());
-        }
+        let mut stack = vec![self];
 
-        self.children
-            .values()
-            .for_each(|x| x.generate_all_data(found_data));
-    }
+        while let Some(node) = stack.pop() {
+            if let Some(data_vec) = &node.word_end_data {
+                found_data.extend(data_vec.iter());
+            }
 
-    /// Recursive function finds every node that is an end of a word and appends
-    /// its data as mutable references to the passed vector.
-    pub(crate) fn generate_all_data_mut<'a>(&'a mut self, found_data: &mut Vec<&'a mut D>) {
-        if let Some(data_vec) = &mut self.word_end_data {
-            found_data.extend(data_vec.iter_mut());
+            let first_child = stack.len();
+            stack.extend(node.children.values());
+            stack[first_child..].reverse();
         }
-
-        self.children
-            .values_mut()
-            .for_each(|x| x.generate_all_data_mut(found_data));
     }
 
-    /// Function pushes data to the association vector.
-    pub(crate) fn push_data(&mut self, data: D) {
-        self.get_association_mut().as_mut().unwrap().push(data);
-    }
+    /// Function finds every node that is an end of a word and appends
+    /// its data as mutable references to the passed vector.
+    pub(crate) fn generate_all_data_mut<'a>(&'a mut self, found_data: &mut Vec<&'a mut D>) {
+        let mut stack = vec![self];
 
-    /// Recursive function for inserting found words from the given node and
-    /// given starting substring.
-    pub(crate) fn find_words(&self, substring: &mut String, found_words: &mut Vec<String>) {
-        if self.is_associated() {
-            found_words.push(substring.to_string());
-        }
+        while let Some(node) = stack.pop() {
+            if let Some(data_vec) = &mut node.word_end_data {
+                found_data.extend(data_vec.iter_mut());
+            }
 
-        for (&character, node) in self.children.iter() {
-            substring.push(character);
-            node.find_words(substring, found_words);
-            substring.pop();
+            let first_child = stack.len();
+            stack.extend(node.children.values_mut());
+            stack[first_child..].reverse();
         }
     }
 
-    /// The recursive function for finding a vector of shortest and longest words in the TrieNode consists of:
-    /// - the DFS tree traversal part for getting to every child node;
-    /// - matching lengths of found words in combination with the passed ordering.
-    pub(crate) fn words_min_max(
-        &self,
+    /// Function that keeps only the data for which 'f' returns true,
+    /// given the word it is associated to. Words that had data before, but
+    /// have none left, are appended to 'emptied_words'.
+    pub(crate) fn retain_data(
+        &mut self,
         substring: &mut String,
-        current_visual_len: usize,
-        found_words: &mut Vec<String>,
-        current_best_len: &mut Option<usize>,
-        #[cfg(feature = "unicode")] is_path_pure_ascii: bool,
-        ord: Ordering,
+        f: &mut impl FnMut(&str, &mut D) -> bool,
+        emptied_words: &mut Vec<String>,
     ) {
-        if self.is_associated() {
-            match current_best_len {
-                Some(best_len) => match current_visual_len.cmp(best_len) {
-                    o if o == ord => {
-                        *best_len = current_visual_len;
-                        found_words.clear();
-                        found_words.push(substring.clone());
-                    }
-                    Ordering::Equal => {
-                        found_words.push(substring.clone());
-                    }
-                    _ => {}
-                },
-                None => {
-                    *current_best_len = Some(current_visual_len);
-                    found_words.push(substring.clone());
+        let start_len = substring.len();
+        let mut stack = vec![(self, None, start_len)];
+
+        while let Some((node, character, word_len)) = stack.pop() {
+            substring.truncate(word_len);
+            if let Some(character) = character {
+                substring.push(character);
+            }
+
+            if let Some(data_vec) = &mut node.word_end_data {
+                let had_data = !data_vec.is_empty();
+                data_vec.retain_mut(|data| f(substring, data));
+
+                if had_data && data_vec.is_empty() {
+                    emptied_words.push(substring.clone());
                 }
             }
+
+            let first_child = stack.len();
+            stack.extend(
+                node.children
+                    .iter_mut()
+                    .map(|(&character, child)| (child, Some(character), substring.len())),
+            );
+            stack[first_child..].reverse();
         }
 
-        for (&character, node) in self.children.iter() {
-            substring.push(character);
+        substring.truncate(start_len);
+    }
+
+    /// Function removes the data matching 'predicate' from the association
+    /// vector and returns it. If the node isn't associated, None is returned.
+    pub(crate) fn remove_data_where(
+        &mut self,
+        mut predicate: impl FnMut(&D) -> bool,
+    ) -> Option<Vec<D>> {
+        let data_vec = self.word_end_data.as_mut()?;
+
+        let mut removed_data = Vec::new();
 
-            #[cfg(feature = "unicode")]
-            let (next_visual_len, next_is_ascii) = if is_path_pure_ascii && character.is_ascii() {
-                (current_visual_len + 1, true)
+        for data in core::mem::take(data_vec) {
+            if predicate(&data) {
+                removed_data.push(data);
             } else {
-                (substring.graphemes(true).count(), false)
-            };
-
-            #[cfg(not(feature = "unicode"))]
-            let next_visual_len = current_visual_len + 1;
-
-            node.words_min_max(
-                substring,
-                next_visual_len,
-                found_words,
-                current_best_len,
-                #[cfg(feature = "unicode")]
-                next_is_ascii,
-                ord,
-            );
-            substring.pop();
+                data_vec.push(data);
+            }
+        }
+
+        Some(removed_data)
+    }
+
+    /// Function that consumes the node and builds a node with the same
+    /// children and word ends, where every piece of data is transformed by 'f'
+    /// given the word it is associated to. Data for which 'f' returns None is dropped.
+    ///
+    /// Every new node is first built with placeholder children in the same storage,
+    /// which are filled in from the old children while walking down with a stack.
+    pub(crate) fn filter_map_data<E>(
+        self,
+        substring: &mut String,
+        f: &mut impl FnMut(&str, D) -> Option<E>,
+    ) -> TrieDataNode<E> {
+        let start_len = substring.len();
+        let mut new_root = TrieDataNode::new();
+        let mut stack = vec![(&mut new_root, self, None, start_len)];
+
+        while let Some((new_node, mut old_node, character, word_len)) = stack.pop() {
+            substring.truncate(word_len);
+            if let Some(character) = character {
+                substring.push(character);
+            }
+
+            new_node.word_end_data = old_node.word_end_data.take().map(|data_vec| {
+                data_vec
+                    .into_iter()
+                    .filter_map(|data| f(substring, data))
+                    .collect()
+            });
+            #[cfg(feature = "subtree-counts")]
+            {
+                new_node.subtree_words = old_node.subtree_words;
+            }
+
+            let mut old_children = Vec::new();
+            new_node.children = core::mem::take(&mut old_node.children).map(|character, child| {
+                old_children.push((character, Some(child)));
+                TrieDataNode::new()
+            });
+            old_children.sort_unstable_by_key(|&(character, _)| character);
+
+            for (&character, new_child) in new_node.children.iter_mut() {
+                let position = old_children
+                    .binary_search_by_key(&character, |&(character, _)| character)
+                    .unwrap();
+                let old_child = old_children[position].1.take().unwrap();
+
+                stack.push((new_child, old_child, Some(character), substring.len()));
+            }
         }
+
+        substring.truncate(start_len);
+        new_root
+    }
+
+    /// Function pushes data to the association vector.
+    pub(crate) fn push_data(&mut self, data: D) {
+        self.get_association_mut().as_mut().unwrap().push(data);
     }
 
     /// Function resets the association of a word and returns the
@@ -193,46 +245,64 @@ impl<D> TrieDataNode<D> {
         return_data
     }
 
-    /// Recursive function for removing and freeing memory of a word that is not needed anymore.
-    /// The algorithm first finds the last node of a word given in the form of a character iterator,
-    /// then it frees the maps and unwinds to the first node that should not be deleted.
-    /// The first node that should not be deleted is either:
-    /// - the root node
-    /// - the node that has multiple words branching from it
-    /// - the node that represents an end to some word with the same prefix
-    /// The last node's data is propagated all the way to the final return
-    /// with the help of auxiliary 'RemoveData<D>' struct.
-    pub(crate) fn remove_one_word<'b>(
+    /// Function for removing and freeing memory of a word that is not needed anymore,
+    /// given in the form of a character iterator. The word must be in the trie.
+    /// Every node on its path loses the word from its cached number of words.
+    /// Returns the data of the word along with the number of freed nodes.
+    pub(crate) fn remove_one_word(
         &mut self,
-        mut characters: impl Iterator<Item = char>,
-    ) -> RemoveData<D> {
-        let next_character = match characters.next() {
-            None => {
-                return RemoveData {
-                    must_keep: false,
-                    data: self.disassociate(),
-                }
-            }
-            Some(char) => char,
-        };
+        characters: impl Iterator<Item = char>,
+        layout: StorageLayout,
+    ) -> (WordEnd<D>, usize) {
+        let characters = characters.collect::<Vec<_>>();
+        self.subtract_path_words(characters.iter().copied(), 1);
 
-        let next_node = self.children.get_mut(next_character).unwrap();
-        let must_keep = next_node.remove_one_word(characters);
+        self.remove_word_path(&characters, layout, Self::disassociate)
+    }
 
-        if self.children.len() > 1 || must_keep.must_keep {
-            return RemoveData {
-                must_keep: true,
-                data: must_keep.data,
-            };
+    /// Function walks the path of a word given in the form of a character iterator,
+    /// subtracting 'n' from the cached number of words of every node on it,
+    /// including this node. Every node on the path must exist.
+    #[cfg(feature = "subtree-counts")]
+    pub(crate) fn subtract_path_words(&mut self, characters: impl Iterator<Item = char>, n: usize) {
+        let mut current = self;
+        current.subtree_words -= n;
+
+        for character in characters {
+            current = current.children.get_mut(character).unwrap();
+            current.subtree_words -= n;
         }
-        self.clear_children();
+    }
 
-        RemoveData {
-            must_keep: self.is_associated(),
-            data: must_keep.data,
-        }
+    /// Without cached numbers of words, there is nothing to update on the path.
+    #[cfg(not(feature = "subtree-counts"))]
+    pub(crate) fn subtract_path_words(
+        &mut self,
+        _characters: impl Iterator<Item = char>,
+        _n: usize,
+    ) {
+    }
+
+    /// Function adds a word to the cached number of words in the subtree.
+    #[cfg(feature = "subtree-counts")]
+    pub(crate) fn increment_subtree_words(&mut self) {
+        self.subtree_words += 1;
     }
 
+    /// Without cached numbers of words, there is nothing to update.
+    #[cfg(not(feature = "subtree-counts"))]
+    pub(crate) fn increment_subtree_words(&mut self) {}
+
+    /// Function adds 'n' words to the cached number of words in the subtree.
+    #[cfg(feature = "subtree-counts")]
+    fn add_subtree_words(&mut self, n: usize) {
+        self.subtree_words += n;
+    }
+
+    /// Without cached numbers of words, there is nothing to update.
+    #[cfg(not(feature = "subtree-counts"))]
+    fn add_subtree_words(&mut self, _n: usize) {}
+
     /// Function marks the node as an end of a word.
     pub(crate) fn associate(&mut self) {
         self.word_end_data = Some(ThinVec::new());
@@ -258,78 +328,153 @@ impl<D> TrieDataNode<D> {
         &mut self.word_end_data
     }
 
-    /// Function removes all children of a node.
-    pub(crate) fn clear_children(&mut self) {
-        self.children = Default::default();
+    /// Function splits the node into its children and its association.
+    pub(crate) fn into_parts(mut self) -> (ChildStorage<Self>, WordEnd<D>) {
+        (
+            core::mem::take(&mut self.children),
+            self.word_end_data.take(),
+        )
     }
 }
 
-impl<D> ops::AddAssign for TrieDataNode<D> {
-    /// Overriding the += operator on nodes.
-    /// Function adds two nodes based on the principle:
+impl<D> TrieNode for TrieDataNode<D> {
+    fn children(&self) -> &ChildStorage<Self> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut ChildStorage<Self> {
+        &mut self.children
+    }
+
+    fn is_associated(&self) -> bool {
+        self.word_end_data.is_some()
+    }
+
+    #[cfg(feature = "subtree-counts")]
+    fn subtree_words(&self) -> usize {
+        self.subtree_words
+    }
+
+    /// Without cached numbers of words, the subtree is counted.
+    #[cfg(not(feature = "subtree-counts"))]
+    fn subtree_words(&self) -> usize {
+        count_words(self)
+    }
+
+    fn association_heap_bytes(&self) -> usize {
+        match &self.word_end_data {
+            Some(data_vec) if data_vec.capacity() > 0 => {
+                2 * size_of::<usize>() + data_vec.capacity() * size_of::<D>()
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl<D> TrieDataNode<D> {
+    /// Function merges the 'rhs' node into this node, for the += operator on tries,
+    /// based on the principle:
     /// for every child node and character in the 'rhs' node:
     /// - if the self node doesn't have that character in its children map,
-    /// simply move the pointer to the self's children map without any extra cost;
+    ///   simply move the node into the self's children map without any extra cost;
     /// - if the self node has that character, the node of that character (self's child)
-    /// is added with the 'rhc's' node.
-    /// An edge case exists when the 'rhc's' node has an association but self's node doesn't.
-    /// That association is handled based on the result of 'rhc_next_node.word_end_data'.
-    /// On Some(data), the self node vector is initialized with the 'rhc' node vector.
-    fn add_assign(&mut self, rhs: Self) {
-        for (char, mut rhs_next_node) in rhs.children.into_iter() {
-            // Does self contain the character?
-            match self.children.remove(char) {
-                // The whole node is removed, as owned, operated on and returned in self's children.
-                Some(mut self_next_node) => {
-                    // Edge case: associate self node if the other node is also associated
-                    // Example: when adding 'word' to 'word1', 'd' on 'word' needs to be associated
-                    if let Some(data_vec_rhs) = rhs_next_node.word_end_data.take() {
-                        if let Some(data_vec_self) = &mut self_next_node.word_end_data {
-                            data_vec_self.extend(data_vec_rhs);
-                        } else {
-                            self_next_node.word_end_data = Some(data_vec_rhs);
-                        }
-                    }
-
-                    self_next_node += rhs_next_node;
-                    self.children.insert_direct(char, self_next_node);
+    ///   is merged with the 'rhs's' node.
+    ///
+    /// Merged node pairs wait on a stack instead of recursing. An edge case exists
+    /// when the 'rhs's' node has an association but self's node doesn't.
+    /// That association is handled based on the result of 'rhs_node.word_end_data'.
+    /// On Some(data), the self node vector is initialized with the 'rhs' node vector.
+    ///
+    /// Moved nodes keep their cached number of words, while the merged
+    /// nodes add the number of words that the merge brings into their subtree.
+    /// Moved nodes also keep the storage of their children, while new
+    /// children are stored according to 'layout'.
+    ///
+    /// Returns the number of nodes below 'rhs' that were merged into existing nodes.
+    pub(crate) fn merge(&mut self, rhs: Self, layout: StorageLayout) -> usize {
+        let mut new_words = merged_new_words(self, &rhs).into_iter();
+        let mut stack = vec![(self, rhs)];
+        let mut merged_nodes = 0;
+
+        while let Some((self_node, mut rhs_node)) = stack.pop() {
+            merged_nodes += 1;
+            self_node.add_subtree_words(new_words.next().unwrap_or(0));
+
+            if let Some(data_vec_rhs) = rhs_node.word_end_data.take() {
+                if let Some(data_vec_self) = &mut self_node.word_end_data {
+                    data_vec_self.extend(data_vec_rhs);
+                } else {
+                    self_node.word_end_data = Some(data_vec_rhs);
                 }
-                // Self doesn't contain the character, no conflict arises.
-                // The whole 'rhs' node is just moved from 'rhs' into self.
-                None => {
-                    self.children.insert_direct(char, rhs_next_node);
+            }
+
+            let mut merged_children = Vec::new();
+
+            for (char, rhs_next_node) in core::mem::take(&mut rhs_node.children) {
+                if self_node.children.get(char).is_some() {
+                    merged_children.push((char, rhs_next_node));
+                } else {
+                    self_node
+                        .children
+                        .insert_direct(char, rhs_next_node, layout);
                 }
             }
+
+            // Pairs are pushed in the order of their characters, the same
+            // order in which their new words were counted.
+            merged_children.sort_unstable_by_key(|&(char, _)| char);
+            let mut self_next_nodes = self_node
+                .children
+                .iter_mut()
+                .filter(|(char, _)| {
+                    merged_children
+                        .binary_search_by_key(*char, |(char, _)| *char)
+                        .is_ok()
+                })
+                .collect::<Vec<_>>();
+            self_next_nodes.sort_unstable_by_key(|&(char, _)| *char);
+
+            stack.extend(
+                self_next_nodes
+                    .into_iter()
+                    .map(|(_, self_next_node)| self_next_node)
+                    .zip(
+                        merged_children
+                            .into_iter()
+                            .map(|(_, rhs_next_node)| rhs_next_node),
+                    ),
+            );
         }
+
+        // The pair of the merged roots isn't below 'rhs'.
+        merged_nodes - 1
+    }
+}
+
+impl<D> Drop for TrieDataNode<D> {
+    fn drop(&mut self) {
+        drop_children(&mut self.children, |node| &mut node.children);
     }
 }
 
 impl<D: PartialEq> PartialEq for TrieDataNode<D> {
     /// Operation == can be applied only to TrieNodes whose data implements PartialEq.
     fn eq(&self, other: &Self) -> bool {
-        // If keys aren't equal, nodes aren't equal.
-        if !self.children.has_same_keys(&other.children) {
-            return false;
-        }
-
-        // If associations aren't equal, two nodes aren't equal.
-        if !match (&self.word_end_data, &other.word_end_data) {
-            (Some(self_vec), Some(other_vec)) => {
-                // If they both have an association, return true only if the data is identical
-                self_vec.len() == other_vec.len() && self_vec.iter().all(|k| other_vec.contains(k))
-            }
-            // If they both don't have an association, return true
-            (None, None) => true,
-            _ => false,
-        } {
-            return false;
-        }
-
-        // Every child node that has the same key (character) must be equal.
-        self.children
-            .iter()
-            .map(|(char, self_child)| (self_child, other.children.get(*char).unwrap()))
-            .all(|(self_child, other_child)| other_child == self_child)
+        nodes_eq(
+            self,
+            other,
+            |node| &node.children,
+            |node, other| match (&node.word_end_data, &other.word_end_data) {
+                (Some(self_vec), Some(other_vec)) => {
+                    // If they both have an association, return true only if the data is identical
+                    self_vec.len() == other_vec.len()
+                        && self_vec.iter().all(|k| other_vec.contains(k))
+                }
+                // If they both don't have an association, return true
+                (None, None) => true,
+                _ => false,
+            },
+        )
     }
 }
 
@@ -0,0 +1,156 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Debug;
+
+use crate::child_storage::{ChildStorage, StorageLayout};
+use crate::trie_node::TrieNode;
+use crate::trie_node::traversal::{count_words, drop_children, nodes_eq, walk};
+#[cfg(feature = "serde")]
+use serde_crate::{Deserialize, Serialize};
+
+/// Singular trie node that represents its children and the value
+/// of the word ending on it.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct TrieMapNode<V> {
+    #[cfg_attr(feature = "serde", serde(rename = "c"))]
+    pub(crate) children: ChildStorage<TrieMapNode<V>>,
+    #[cfg_attr(feature = "serde", serde(rename = "v"))]
+    value: Option<V>,
+}
+
+impl<V> Default for TrieMapNode<V> {
+    fn default() -> Self {
+        Self {
+            children: ChildStorage::default(),
+            value: None,
+        }
+    }
+}
+
+impl<V> TrieMapNode<V> {
+    /// Returns a new instance of a TrieNode.
+    pub(crate) fn new() -> Self {
+        TrieMapNode {
+            children: Default::default(),
+            value: None,
+        }
+    }
+
+    /// Function for inserting found words and references to their
+    /// values from the given node and given starting substring.
+    pub(crate) fn find_entries<'a>(
+        &'a self,
+        substring: &mut String,
+        found_entries: &mut Vec<(String, &'a V)>,
+    ) {
+        walk(
+            self,
+            substring,
+            (),
+            |node| &node.children,
+            |node, _, word, _| {
+                if let Some(value) = &node.value {
+                    found_entries.push((word.to_string(), value));
+                }
+
+                Some(())
+            },
+        );
+    }
+
+    /// Function for removing and freeing memory of a word that is not needed anymore,
+    /// given in the form of a character iterator, and returning its value.
+    /// The path of the word must be in the trie.
+    pub(crate) fn remove_one_word(
+        &mut self,
+        characters: impl Iterator<Item = char>,
+        layout: StorageLayout,
+    ) -> Option<V> {
+        let characters = characters.collect::<Vec<_>>();
+
+        let (value, _) = self.remove_word_path(&characters, layout, Self::take_value);
+        value
+    }
+
+    /// Function sets the value of the word ending on this node
+    /// and returns the previous value.
+    pub(crate) fn replace_value(&mut self, value: V) -> Option<V> {
+        self.value.replace(value)
+    }
+
+    /// Function unmarks the node as an end of a word and returns the value.
+    pub(crate) fn take_value(&mut self) -> Option<V> {
+        self.value.take()
+    }
+
+    /// Function returns the value of the word ending on this node.
+    pub(crate) fn value(&self) -> Option<&V> {
+        self.value.as_ref()
+    }
+
+    /// Function returns the mutable value of the word ending on this node.
+    pub(crate) fn value_mut(&mut self) -> Option<&mut V> {
+        self.value.as_mut()
+    }
+
+    /// Function returns true if the node is an end of a word.
+    pub(crate) fn is_associated(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+impl<V> TrieNode for TrieMapNode<V> {
+    fn children(&self) -> &ChildStorage<Self> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut ChildStorage<Self> {
+        &mut self.children
+    }
+
+    fn is_associated(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Map nodes don't cache their numbers of words, so the subtree is counted.
+    fn subtree_words(&self) -> usize {
+        count_words(self)
+    }
+}
+
+impl<V> Drop for TrieMapNode<V> {
+    fn drop(&mut self) {
+        drop_children(&mut self.children, |node| &mut node.children);
+    }
+}
+
+impl<V: PartialEq> PartialEq for TrieMapNode<V> {
+    /// Operation == can be applied only to TrieNodes whose values implement PartialEq.
+    fn eq(&self, other: &Self) -> bool {
+        // If values aren't equal, two nodes aren't equal.
+        nodes_eq(
+            self,
+            other,
+            |node| &node.children,
+            |node, other| node.value == other.value,
+        )
+    }
+}
+
+impl<V: Debug> Debug for TrieMapNode<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("Node");
+
+        if let Some(value) = &self.value {
+            s.field("value", value);
+        }
+
+        s.field("children", &self.children);
+        s.finish()
+    }
+}
@@ -1,13 +1,15 @@
-use std::cmp::Ordering;
-use std::ops;
-
-use crate::child_storage::ChildStorage;
+use crate::child_storage::{ChildStorage, StorageLayout};
+use crate::trie_node::TrieNode;
+#[cfg(not(feature = "subtree-counts"))]
+use crate::trie_node::traversal::count_words;
+use crate::trie_node::traversal::{drop_children, merged_new_words, nodes_eq};
+use alloc::vec;
+use alloc::vec::Vec;
 #[cfg(feature = "serde")]
 use serde_crate::{Deserialize, Serialize};
-#[cfg(feature = "unicode")]
-use unicode_segmentation::UnicodeSegmentation;
 
-/// Singular trie node that represents its children and a marker for word ending.
+/// Singular trie node that represents its children, a marker for word ending
+/// and, with the 'subtree-counts' feature, the cached number of words in its subtree.
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -19,6 +21,9 @@ pub struct TrieDatalessNode {
     pub(crate) children: ChildStorage<TrieDatalessNode>,
     #[cfg_attr(feature = "serde", serde(rename = "we"))]
     word_end: bool,
+    #[cfg(feature = "subtree-counts")]
+    #[cfg_attr(feature = "serde", serde(rename = "sw"))]
+    subtree_words: usize,
 }
 
 impl TrieDatalessNode {
@@ -27,135 +32,70 @@ impl TrieDatalessNode {
         TrieDatalessNode {
             children: Default::default(),
             word_end: false,
+            #[cfg(feature = "subtree-counts")]
+            subtree_words: 0,
         }
     }
 
-    /// Recursive function for inserting found words from the given node and
-    /// given starting substring.
-    pub(crate) fn find_words(&self, substring: &mut String, found_words: &mut Vec<String>) {
-        if self.is_associated() {
-            found_words.push(substring.to_string());
-        }
+    /// Function for removing and freeing memory of a word that is not needed anymore,
+    /// given in the form of a character iterator. The word must be in the trie.
+    /// Every node on its path loses the word from its cached number of words.
+    /// Returns the number of freed nodes.
+    pub(crate) fn remove_one_word(
+        &mut self,
+        characters: impl Iterator<Item = char>,
+        layout: StorageLayout,
+    ) -> usize {
+        let characters = characters.collect::<Vec<_>>();
+        self.subtract_path_words(characters.iter().copied(), 1);
+
+        let (_, freed) = self.remove_word_path(&characters, layout, Self::disassociate);
+        freed
+    }
 
-        for (&character, node) in self.children.iter() {
-            substring.push(character);
-            node.find_words(substring, found_words);
-            substring.pop();
+    /// Function walks the path of a word given in the form of a character iterator,
+    /// subtracting 'n' from the cached number of words of every node on it,
+    /// including this node. Every node on the path must exist.
+    #[cfg(feature = "subtree-counts")]
+    pub(crate) fn subtract_path_words(&mut self, characters: impl Iterator<Item = char>, n: usize) {
+        let mut current = self;
+        current.subtree_words -= n;
+
+        for character in characters {
+            current = current.children.get_mut(character).unwrap();
+            current.subtree_words -= n;
         }
     }
 
-    /// The recursive function for finding a vector of shortest and longest words in the TrieNode consists of:
-    /// - the DFS tree traversal part for getting to every child node;
-    /// - matching lengths of found words in combination with the passed ordering.
-    pub(crate) fn words_min_max(
-        &self,
-        substring: &mut String,
-        current_visual_len: usize,
-        found_words: &mut Vec<String>,
-        current_best_len: &mut Option<usize>,
-        #[cfg(feature = "unicode")] is_path_pure_ascii: bool,
-        ord: Ordering,
+    /// Without cached numbers of words, there is nothing to update on the path.
+    #[cfg(not(feature = "subtree-counts"))]
+    pub(crate) fn subtract_path_words(
+        &mut self,
+        _characters: impl Iterator<Item = char>,
+        _n: usize,
     ) {
-        if self.is_associated() {
-            match current_best_len {
-                Some(best_len) => match current_visual_len.cmp(best_len) {
-                    o if o == ord => {
-                        *best_len = current_visual_len;
-                        found_words.clear();
-                        found_words.push(substring.clone());
-                    }
-                    Ordering::Equal => {
-                        found_words.push(substring.clone());
-                    }
-                    _ => {}
-                },
-                None => {
-                    *current_best_len = Some(current_visual_len);
-                    found_words.push(substring.clone());
-                }
-            }
-        }
-
-        for (&character, node) in self.children.iter() {
-            substring.push(character);
-
-            #[cfg(feature = "unicode")]
-            let (next_visual_len, next_is_ascii) = if is_path_pure_ascii && character.is_ascii() {
-                (current_visual_len + 1, true)
-            } else {
-                (substring.graphemes(true).count(), false)
-            };
-
-            #[cfg(not(feature = "unicode"))]
-            let next_visual_len = current_visual_len + 1;
-
-            node.words_min_max(
-                substring,
-                next_visual_len,
-                found_words,
-                current_best_len,
-                #[cfg(feature = "unicode")]
-                next_is_ascii,
-                ord,
-            );
-            substring.pop();
-        }
     }
 
-    /// Recursive function that drops all children maps
-    /// regardless of having multiple words branching from them or not.
-    /// Counts the number of words removed.
-    pub(crate) fn remove_all_words(&mut self) -> usize {
-        let num_removed = self
-            .children
-            .values_mut()
-            .map(|child| child.remove_all_words())
-            .sum::<usize>()
-            + self.is_associated() as usize;
-
-        self.clear_children();
-
-        num_removed
-    }
-
-    /// Recursive function that counts the number of words from a starting node.
-    pub(crate) fn count_words(&self) -> usize {
-        self.children
-            .values()
-            .map(|child| child.count_words())
-            .sum::<usize>()
-            + self.is_associated() as usize
-    }
-
-    /// Recursive function for removing and freeing memory of a word that is not needed anymore.
-    /// The algorithm first finds the last node of a word given in the form of a character iterator,
-    /// then it frees the maps and unwinds to the first node that should not be deleted.
-    /// The first node that should not be deleted is either:
-    /// - the root node
-    /// - the node that has multiple words branching from it
-    /// - the node that represents an end to some word with the same prefix
-    /// The last node's data is propagated all the way to the final return
-    /// with the help of auxiliary 'RemoveData<D>' struct.
-    pub(crate) fn remove_one_word(&mut self, mut characters: impl Iterator<Item = char>) -> bool {
-        let next_character = match characters.next() {
-            None => {
-                self.disassociate();
-                return false;
-            }
-            Some(char) => char,
-        };
-
-        let next_node = self.children.get_mut(next_character).unwrap();
-        let must_keep = next_node.remove_one_word(characters);
+    /// Function adds a word to the cached number of words in the subtree.
+    #[cfg(feature = "subtree-counts")]
+    pub(crate) fn increment_subtree_words(&mut self) {
+        self.subtree_words += 1;
+    }
 
-        if self.children.len() > 1 || must_keep {
-            return true;
-        }
-        self.clear_children();
+    /// Without cached numbers of words, there is nothing to update.
+    #[cfg(not(feature = "subtree-counts"))]
+    pub(crate) fn increment_subtree_words(&mut self) {}
 
-        self.is_associated()
+    /// Function adds 'n' words to the cached number of words in the subtree.
+    #[cfg(feature = "subtree-counts")]
+    fn add_subtree_words(&mut self, n: usize) {
+        self.subtree_words += n;
     }
 
+    /// Without cached numbers of words, there is nothing to update.
+    #[cfg(not(feature = "subtree-counts"))]
+    fn add_subtree_words(&mut self, _n: usize) {}
+
     /// Function marks the node as an end of a word.
     pub(crate) fn associate(&mut self) {
         self.word_end = true;
@@ -174,60 +114,123 @@ impl TrieDatalessNode {
     pub(crate) fn clear_children(&mut self) {
         self.children = Default::default();
     }
+
+    /// Function splits the node into its children and its word end marker.
+    pub(crate) fn into_parts(mut self) -> (ChildStorage<Self>, bool) {
+        (core::mem::take(&mut self.children), self.word_end)
+    }
+}
+
+impl TrieNode for TrieDatalessNode {
+    fn children(&self) -> &ChildStorage<Self> {
+        &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut ChildStorage<Self> {
+        &mut self.children
+    }
+
+    fn is_associated(&self) -> bool {
+        self.word_end
+    }
+
+    #[cfg(feature = "subtree-counts")]
+    fn subtree_words(&self) -> usize {
+        self.subtree_words
+    }
+
+    /// Without cached numbers of words, the subtree is counted.
+    #[cfg(not(feature = "subtree-counts"))]
+    fn subtree_words(&self) -> usize {
+        count_words(self)
+    }
 }
 
-impl ops::AddAssign for TrieDatalessNode {
-    /// Overriding the += operator on nodes.
-    /// Function adds two nodes based on the principle:
+impl TrieDatalessNode {
+    /// Function merges the 'rhs' node into this node, for the += operator on tries,
+    /// based on the principle:
     /// for every child node and character in the 'rhs' node:
     /// - if the self node doesn't have that character in its children map,
-    /// simply move the pointer to the self's children map without any extra cost;
+    ///   simply move the node into the self's children map without any extra cost;
     /// - if the self node has that character, the node of that character (self's child)
-    /// is added with the 'rhs's' node.
-    /// An edge case exists when the 'rhs's' node has an association but self's node doesn't.
-    /// That association is handled based on the result of 'rhc_next_node.word_end'.
-    /// On true, the self node vector is initialized with the 'rhc' node vector.
-    fn add_assign(&mut self, rhs: Self) {
-        for (char, rhs_next_node) in rhs.children.into_iter() {
-            // Does self contain the character?
-            match self.children.remove(char) {
-                // The whole node is removed, as owned, operated on and returned in self's children.
-                Some(mut self_next_node) => {
-                    // Edge case: associate self node if the other node is also associated
-                    // Example: when adding 'word' to 'word1', 'd' on 'word' needs to be associated
-                    if rhs_next_node.word_end {
-                        self_next_node.word_end = true;
-                    }
-
-                    self_next_node += rhs_next_node;
-                    self.children.insert_direct(char, self_next_node);
-                }
-                // Self doesn't contain the character, no conflict arises.
-                // The whole 'rhs' node is just moved from 'rhs' into self.
-                None => {
-                    self.children.insert_direct(char, rhs_next_node);
+    ///   is merged with the 'rhs's' node.
+    ///
+    /// Merged node pairs wait on a stack instead of recursing, and a node
+    /// is associated if either node of its pair is associated.
+    ///
+    /// Moved nodes keep their cached number of words, while the merged
+    /// nodes add the number of words that the merge brings into their subtree.
+    /// Moved nodes also keep the storage of their children, while new
+    /// children are stored according to 'layout'.
+    ///
+    /// Returns the number of nodes below 'rhs' that were merged into existing nodes.
+    pub(crate) fn merge(&mut self, rhs: Self, layout: StorageLayout) -> usize {
+        let mut new_words = merged_new_words(self, &rhs).into_iter();
+        let mut stack = vec![(self, rhs)];
+        let mut merged_nodes = 0;
+
+        while let Some((self_node, mut rhs_node)) = stack.pop() {
+            merged_nodes += 1;
+            self_node.add_subtree_words(new_words.next().unwrap_or(0));
+            self_node.word_end |= rhs_node.word_end;
+
+            let mut merged_children = Vec::new();
+
+            for (char, rhs_next_node) in core::mem::take(&mut rhs_node.children) {
+                if self_node.children.get(char).is_some() {
+                    merged_children.push((char, rhs_next_node));
+                } else {
+                    self_node
+                        .children
+                        .insert_direct(char, rhs_next_node, layout);
                 }
             }
+
+            // Pairs are pushed in the order of their characters, the same
+            // order in which their new words were counted.
+            merged_children.sort_unstable_by_key(|&(char, _)| char);
+            let mut self_next_nodes = self_node
+                .children
+                .iter_mut()
+                .filter(|(char, _)| {
+                    merged_children
+                        .binary_search_by_key(*char, |(char, _)| *char)
+                        .is_ok()
+                })
+                .collect::<Vec<_>>();
+            self_next_nodes.sort_unstable_by_key(|&(char, _)| *char);
+
+            stack.extend(
+                self_next_nodes
+                    .into_iter()
+                    .map(|(_, self_next_node)| self_next_node)
+                    .zip(
+                        merged_children
+                            .into_iter()
+                            .map(|(_, rhs_next_node)| rhs_next_node),
+                    ),
+            );
         }
+
+        // The pair of the merged roots isn't below 'rhs'.
+        merged_nodes - 1
+    }
+}
+
+impl Drop for TrieDatalessNode {
+    fn drop(&mut self) {
+        drop_children(&mut self.children, |node| &mut node.children);
     }
 }
 
 impl PartialEq for TrieDatalessNode {
     fn eq(&self, other: &Self) -> bool {
-        // If keys aren't equal, nodes aren't equal.
-        if !self.children.has_same_keys(&other.children) {
-            return false;
-        }
-
         // If the node on one trie is a word end, and on the other it isn't, two nodes aren't equal.
-        if self.word_end != other.word_end {
-            return false;
-        }
-
-        // Every child node that has the same key (character) must be equal.
-        self.children
-            .iter()
-            .map(|(char, self_child)| (self_child, other.children.get(*char).unwrap()))
-            .all(|(self_child, other_child)| other_child == self_child)
+        nodes_eq(
+            self,
+            other,
+            |node| &node.children,
+            |node, other| node.word_end == other.word_end,
+        )
     }
 }
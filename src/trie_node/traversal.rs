@@ -0,0 +1,261 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::child_storage::ChildStorage;
+use crate::trie_node::TrieNode;
+#[cfg(feature = "unicode")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Function visits every node in the subtree of 'node' in preorder, with a stack
+/// instead of recursion, so that tries with very long words don't overflow the
+/// thread stack. 'substring' holds the word of the visited node while 'visit' runs,
+/// and is left as it was passed once the walk is done.
+///
+/// Besides the node and its word, 'visit' is given the character of the node,
+/// which is None for 'node' itself, and the state returned for the node's parent,
+/// which is 'state' for 'node' itself. The state returned for a node is passed
+/// on to its children, and returning None skips the node's children.
+pub(crate) fn walk<'a, N, S: Copy>(
+    node: &'a N,
+    substring: &mut String,
+    state: S,
+    children: impl Fn(&'a N) -> &'a ChildStorage<N>,
+    mut visit: impl FnMut(&'a N, Option<char>, &str, S) -> Option<S>,
+) {
+    let start_len = substring.len();
+    let mut stack = vec![(node, None, start_len, state)];
+
+    while let Some((node, character, word_len, parent_state)) = stack.pop() {
+        substring.truncate(word_len);
+        if let Some(character) = character {
+            substring.push(character);
+        }
+
+        let Some(state) = visit(node, character, substring, parent_state) else {
+            continue;
+        };
+
+        // Children are pushed in reverse, so that they are visited in order.
+        let first_child = stack.len();
+        stack.extend(
+            children(node)
+                .iter()
+                .map(|(&character, child)| (child, Some(character), substring.len(), state)),
+        );
+        stack[first_child..].reverse();
+    }
+
+    substring.truncate(start_len);
+}
+
+/// Function appends every word in the subtree of 'node' to 'found_words',
+/// where 'substring' is the word of 'node'.
+pub(crate) fn find_words<N: TrieNode>(
+    node: &N,
+    substring: &mut String,
+    found_words: &mut Vec<String>,
+) {
+    walk(node, substring, (), N::children, |node, _, word, _| {
+        if node.is_associated() {
+            found_words.push(word.to_string());
+        }
+
+        Some(())
+    });
+}
+
+/// Function returns the number of words in the subtree of 'node',
+/// including the word ending on the node itself.
+#[cfg(any(feature = "data", not(feature = "subtree-counts")))]
+pub(crate) fn count_words<N: TrieNode>(node: &N) -> usize {
+    let mut word_count = 0;
+    let mut stack = vec![node];
+
+    while let Some(node) = stack.pop() {
+        word_count += node.is_associated() as usize;
+        stack.extend(node.children().values());
+    }
+
+    word_count
+}
+
+/// Function returns the number of nodes in the subtree of 'node',
+/// not including the node itself.
+pub(crate) fn count_nodes<N: TrieNode>(node: &N) -> usize {
+    let mut node_count = 0;
+    let mut stack = node.children().values().collect::<Vec<_>>();
+
+    while let Some(node) = stack.pop() {
+        node_count += 1;
+        stack.extend(node.children().values());
+    }
+
+    node_count
+}
+
+/// Function returns the words of the trie with the given root whose visual length
+/// is the largest when 'ord' is Greater, or the smallest when 'ord' is Less.
+/// The visual length of a word is kept for every node on the way, together
+/// with whether the word is pure ASCII, so graphemes are only counted
+/// on the paths that need them.
+pub(crate) fn words_min_max<N: TrieNode>(root: &N, ord: Ordering) -> Vec<String> {
+    let mut found_words = Vec::new();
+    let mut best_len = None;
+
+    walk(
+        root,
+        &mut String::new(),
+        (0, true),
+        N::children,
+        |node, character, word, parent_len| {
+            let current_len = match character {
+                None => parent_len,
+                Some(character) => next_visual_len(word, character, parent_len),
+            };
+
+            if node.is_associated() {
+                match best_len {
+                    Some(best) => match current_len.0.cmp(&best) {
+                        o if o == ord => {
+                            best_len = Some(current_len.0);
+                            found_words.clear();
+                            found_words.push(word.to_string());
+                        }
+                        Ordering::Equal => found_words.push(word.to_string()),
+                        _ => {}
+                    },
+                    None => {
+                        best_len = Some(current_len.0);
+                        found_words.push(word.to_string());
+                    }
+                }
+            }
+
+            Some(current_len)
+        },
+    );
+
+    found_words
+}
+
+/// Function returns the visual length of 'word' ending with 'character', and whether
+/// it is pure ASCII, from the same values of the word without the character.
+#[cfg(feature = "unicode")]
+fn next_visual_len(word: &str, character: char, (len, is_ascii): (usize, bool)) -> (usize, bool) {
+    if is_ascii && character.is_ascii() {
+        (len + 1, true)
+    } else {
+        (word.graphemes(true).count(), false)
+    }
+}
+
+/// Without the 'unicode' feature, every character is counted.
+#[cfg(not(feature = "unicode"))]
+fn next_visual_len(_word: &str, _character: char, (len, _): (usize, bool)) -> (usize, bool) {
+    (len + 1, false)
+}
+
+/// Function returns true if the subtrees of two nodes have the same characters
+/// everywhere and 'same_association' holds for every pair of their nodes.
+pub(crate) fn nodes_eq<N>(
+    node: &N,
+    other: &N,
+    children: impl Fn(&N) -> &ChildStorage<N>,
+    same_association: impl Fn(&N, &N) -> bool,
+) -> bool {
+    let mut stack = vec![(node, other)];
+
+    while let Some((node, other)) = stack.pop() {
+        let (node_children, other_children) = (children(node), children(other));
+
+        // If keys or associations aren't equal, nodes aren't equal.
+        if !node_children.has_same_keys(other_children) || !same_association(node, other) {
+            return false;
+        }
+
+        // Every child node that has the same key (character) must be equal.
+        stack.extend(
+            node_children
+                .iter()
+                .map(|(&character, child)| (child, other_children.get(character).unwrap())),
+        );
+    }
+
+    true
+}
+
+/// Function drops the descendants of a node one by one, taking the children
+/// of every node out before it is dropped, so that dropping a trie with
+/// very long words doesn't recurse through all of its nodes.
+pub(crate) fn drop_children<N>(
+    children: &mut ChildStorage<N>,
+    children_of: impl Fn(&mut N) -> &mut ChildStorage<N>,
+) {
+    if children.is_empty() {
+        return;
+    }
+
+    let mut stack = core::mem::take(children)
+        .into_iter()
+        .map(|(_, child)| child)
+        .collect::<Vec<_>>();
+
+    while let Some(mut node) = stack.pop() {
+        stack.extend(
+            core::mem::take(children_of(&mut node))
+                .into_iter()
+                .map(|(_, child)| child),
+        );
+    }
+}
+
+/// Function returns the number of words that merging 'rhs' into 'node' adds
+/// to the subtree of every node pair that gets merged, in the order in which
+/// the pairs are merged. Pairs are merged with a stack, and the children that
+/// both nodes of a pair have are pushed in the order of their characters.
+#[cfg(feature = "subtree-counts")]
+pub(crate) fn merged_new_words<N: TrieNode>(node: &N, rhs: &N) -> Vec<usize> {
+    let mut new_words = Vec::new();
+    let mut parents = Vec::new();
+    let mut stack = vec![(node, rhs, 0)];
+
+    while let Some((node, rhs, parent)) = stack.pop() {
+        let id = new_words.len();
+        let mut added = (rhs.is_associated() && !node.is_associated()) as usize;
+        let mut merged_children = Vec::new();
+
+        for (&character, rhs_child) in rhs.children().iter() {
+            match node.children().get(character) {
+                Some(child) => merged_children.push((character, child, rhs_child)),
+                // Children that only 'rhs' has are moved with all of their words.
+                None => added += rhs_child.subtree_words(),
+            }
+        }
+
+        new_words.push(added);
+        parents.push(parent);
+
+        merged_children.sort_unstable_by_key(|&(character, ..)| character);
+        stack.extend(
+            merged_children
+                .into_iter()
+                .map(|(_, child, rhs_child)| (child, rhs_child, id)),
+        );
+    }
+
+    // Pairs come after their parents, so every pair is complete
+    // by the time it is added to its parent.
+    for id in (1..new_words.len()).rev() {
+        new_words[parents[id]] += new_words[id];
+    }
+
+    new_words
+}
+
+/// Without cached numbers of words, there is nothing to count for merging.
+#[cfg(not(feature = "subtree-counts"))]
+pub(crate) fn merged_new_words<N: TrieNode>(_node: &N, _rhs: &N) -> Vec<usize> {
+    Vec::new()
+}
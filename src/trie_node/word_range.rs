@@ -0,0 +1,131 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use crate::trie::get_characters;
+use crate::trie_node::TrieNode;
+
+/// Children of a node sorted by their character, and the position of
+/// the next child to visit.
+type Frame<'a, N> = (Vec<(char, &'a N)>, usize);
+
+/// Function returns the children of a node sorted by their character,
+/// which is also the lexicographic order of the words below them.
+pub(crate) fn sorted_children<N: TrieNode>(node: &N) -> Vec<(char, &N)> {
+    let mut children = node
+        .children()
+        .iter()
+        .map(|(&character, child)| (character, child))
+        .collect::<Vec<_>>();
+    children.sort_unstable_by_key(|&(character, _)| character);
+
+    children
+}
+
+/// Iterator over the words of a trie that fall between two bounds,
+/// in lexicographic order. Only the nodes on the path to the lower bound
+/// and the nodes of the returned words are visited.
+pub(crate) struct WordRange<'a, N> {
+    stack: Vec<Frame<'a, N>>,
+    prefix: String,
+    pending: Option<&'a N>,
+    end: Bound<String>,
+}
+
+impl<'a, N: TrieNode> WordRange<'a, N> {
+    /// Returns a new iterator positioned right before the lower bound.
+    /// The path to the lower bound is walked once, leaving every node on
+    /// it with only the children that come after the bound left to visit.
+    pub(crate) fn new(root: &'a N, start: Bound<&str>, end: Bound<&str>) -> Self {
+        let mut range = WordRange {
+            stack: Vec::new(),
+            prefix: String::new(),
+            pending: None,
+            end: end.map(|word| get_characters(word).collect()),
+        };
+
+        let (start_word, inclusive) = match start {
+            Bound::Unbounded => {
+                range.pending = Some(root);
+                return range;
+            }
+            Bound::Included(word) => (word, true),
+            Bound::Excluded(word) => (word, false),
+        };
+
+        let mut current = root;
+
+        for character in get_characters(start_word) {
+            let children = sorted_children(current);
+            let position = children.partition_point(|&(c, _)| c < character);
+
+            match children.get(position) {
+                Some(&(c, next_node)) if c == character => {
+                    range.stack.push((children, position + 1));
+                    range.prefix.push(character);
+                    current = next_node;
+                }
+                _ => {
+                    range.stack.push((children, position));
+                    return range;
+                }
+            }
+        }
+
+        if inclusive {
+            range.pending = Some(current);
+        } else {
+            range.stack.push((sorted_children(current), 0));
+        }
+
+        range
+    }
+
+    /// Function returns true if the current prefix isn't past the upper bound.
+    fn is_before_end(&self) -> bool {
+        match &self.end {
+            Bound::Unbounded => true,
+            Bound::Included(end) => self.prefix <= *end,
+            Bound::Excluded(end) => self.prefix < *end,
+        }
+    }
+}
+
+impl<'a, N: TrieNode> Iterator for WordRange<'a, N> {
+    type Item = (String, &'a N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.pending.take() {
+                // The prefix of a node is smaller than every word below it, so once
+                // it is past the upper bound, every word that follows is as well.
+                if !self.is_before_end() {
+                    self.stack.clear();
+                    return None;
+                }
+
+                self.stack.push((sorted_children(node), 0));
+
+                if node.is_associated() {
+                    return Some((self.prefix.clone(), node));
+                }
+
+                continue;
+            }
+
+            let (children, position) = self.stack.last_mut()?;
+
+            match children.get(*position) {
+                Some(&(character, child)) => {
+                    *position += 1;
+                    self.prefix.push(character);
+                    self.pending = Some(child);
+                }
+                None => {
+                    self.stack.pop();
+                    self.prefix.pop();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::trie::get_characters;
+use crate::trie_node::TrieNode;
+use crate::trie_node::sorted_children;
+use alloc::string::String;
+
+/// Function returns the number of words in the trie that are lexicographically
+/// smaller than 'word', or None if 'word' isn't in the trie.
+/// On every node of the path, the words smaller than 'word' are the node's
+/// own word, which is a prefix of 'word', and the words below the children
+/// with smaller characters, whose counts are cached in them.
+pub(crate) fn rank<N: TrieNode>(root: &N, word: &str) -> Option<usize> {
+    let mut current = root;
+    let mut rank = 0;
+
+    for character in get_characters(word) {
+        rank += current.is_associated() as usize;
+        rank += current
+            .children()
+            .iter()
+            .filter(|&(&c, _)| c < character)
+            .map(|(_, child)| child.subtree_words())
+            .sum::<usize>();
+
+        current = current.children().get(character)?;
+    }
+
+    current.is_associated().then_some(rank)
+}
+
+/// Function returns the word at position 'n' (counting from 0) in the
+/// lexicographic order of the trie's words, or None if there are not
+/// that many words. Whole subtrees that come before the word are skipped
+/// by their cached counts, so only the path to the word is walked.
+pub(crate) fn select<N: TrieNode>(root: &N, mut n: usize) -> Option<String> {
+    if n >= root.subtree_words() {
+        return None;
+    }
+
+    let mut current = root;
+    let mut word = String::new();
+
+    loop {
+        if current.is_associated() {
+            if n == 0 {
+                return Some(word);
+            }
+
+            n -= 1;
+        }
+
+        for (character, child) in sorted_children(current) {
+            if n < child.subtree_words() {
+                word.push(character);
+                current = child;
+                break;
+            }
+
+            n -= child.subtree_words();
+        }
+    }
+}